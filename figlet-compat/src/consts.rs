@@ -1,5 +1,7 @@
 pub const FIGLET_FONT_SUFFIX: &str = ".flf";
 pub const DEFAULT_FONT_FILE: &str = "standard.flf";
+// standard isn't bundled, so this is used when figlet's fonts aren't installed
+pub const FALLBACK_FONT: &str = "small";
 pub const DEFAULT_COLUMNS: u16 = 80;
 
 // this ideally would be a u8 but that would break compatibility
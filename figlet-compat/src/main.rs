@@ -1,7 +1,15 @@
-use std::{io::Write, path::PathBuf, process::exit};
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::exit,
+    sync::Arc,
+};
 
-use clap::{command, Parser};
-use log::{debug, error, info, Level};
+use figurust::{
+    figlet::{FIGfont, SearchPath},
+    settings::Settings,
+};
+use log::{debug, error, info, warn, Level};
 use owo_colors::OwoColorize;
 use settings::FigletSettings;
 
@@ -15,9 +23,7 @@ mod settings;
 mod utils;
 
 fn default_font_dir() -> PathBuf {
-    dirs::data_dir()
-        .expect("Could not find data directory, set --font-dir manually")
-        .join("figurust")
+    SearchPath::user_directory().expect("Could not find data directory, set one with -d")
 }
 
 fn print_usage(bin_name: &str) {
@@ -47,9 +53,74 @@ pub fn main() {
         .init();
     info!("Starting figurust");
 
-    let settings = from_args();
+    let figlet_settings = from_args();
+    debug!("Settings: {:#?}", figlet_settings);
+
+    let mut settings = Settings::from(&figlet_settings);
+    if settings.info_code >= 0 {
+        utils::print_info(&settings);
+        exit(0);
+    }
+
+    let font = match load_font(&figlet_settings.font_name, &settings) {
+        Ok(font) => font,
+        Err(err) if figlet_settings.font_name == DEFAULT_FONT_FILE => {
+            eprintln!("{DEFAULT_FONT_FILE}: {err}, using the bundled {FALLBACK_FONT} font");
+            settings.font = FALLBACK_FONT.to_string();
+            match settings.load_font() {
+                Ok(font) => font,
+                Err(err) => {
+                    error!("{FALLBACK_FONT}: Unable to open font file: {err}");
+                    exit(1);
+                }
+            }
+        }
+        Err(err) => {
+            error!(
+                "{}: Unable to open font file: {err}",
+                figlet_settings.font_name
+            );
+            exit(1);
+        }
+    };
+
+    let message = match figlet_settings.message {
+        Some(message) => message,
+        None => {
+            let mut buf = String::new();
+            if let Err(err) = std::io::stdin().read_to_string(&mut buf) {
+                error!("Failed to read from stdin: {err}");
+                exit(1);
+            }
+            buf
+        }
+    };
+
+    match font.convert(message, &settings) {
+        Ok(output) => print!("{output}"),
+        Err(err) => {
+            error!("Failed to convert text: {err}");
+            exit(1);
+        }
+    }
+}
+
+/// Like figlet, a font name with a path in it is loaded from that path
+/// instead of the font directory
+fn load_font(font_name: &str, settings: &Settings) -> Result<Arc<FIGfont>, String> {
+    if !font_name.contains(['/', '\\']) {
+        return settings.load_font().map_err(|err| err.to_string());
+    }
 
-    debug!("Settings: {:#?}", settings);
+    let with_suffix = format!("{font_name}{FIGLET_FONT_SUFFIX}");
+    let path = [font_name, &with_suffix]
+        .into_iter()
+        .map(Path::new)
+        .find(|path| path.is_file())
+        .unwrap_or(Path::new(&with_suffix));
+    FIGfont::from_file(path)
+        .map(Arc::new)
+        .map_err(|err| err.to_string())
 }
 
 pub fn from_args() -> FigletSettings {
@@ -57,19 +128,19 @@ pub fn from_args() -> FigletSettings {
 
     let mut bin_name = args.first().unwrap().as_str();
     if bin_name.contains('/') {
-        bin_name = bin_name.split('/').last().unwrap();
+        bin_name = bin_name.split('/').next_back().unwrap();
     } else if bin_name.contains('\\') {
-        bin_name = bin_name.split('\\').last().unwrap();
+        bin_name = bin_name.split('\\').next_back().unwrap();
     }
 
-    println!("bin_name: {bin_name}");
+    debug!("bin_name: {bin_name}");
 
     // enabling this breaks some compatibility with figlet
     // figlet uses 0 for any invalid arguments
     // but with this argument we will print an error and exit
     let mut show_errors = false;
 
-    let mut font_name = "standard.flf".to_string();
+    let mut font_name = DEFAULT_FONT_FILE.to_string();
     let mut font_directory = default_font_dir();
     let mut smush_override = SMO_NO;
     let mut smush_mode = None::<Smushmode>;
@@ -81,78 +152,103 @@ pub fn from_args() -> FigletSettings {
 
     let mut message: Option<String> = None;
 
-    let mut consumed_next = false;
+    // like getopt, everything after `--` is part of the message
+    let mut options_done = false;
+    let mut args_iter = args[1..].iter();
 
-    for (x, arg) in args[1..].iter().enumerate() {
-        if consumed_next {
-            consumed_next = false;
+    while let Some(arg) = args_iter.next() {
+        // a lone `-` isn't an option, so figlet prints it
+        if options_done || arg == "-" || !arg.starts_with('-') {
+            // figlet adds all arguments to the message
+            // unless they start with '-'
+            if let Some(message) = &mut message {
+                message.push(' ');
+                message.push_str(arg);
+            } else {
+                message = Some(arg.to_string());
+            }
             continue;
         }
-        // adjust for the slice
-        let x = x + 1;
-        if arg.len() > 1 && arg.starts_with('-') {
-            match &arg[1..] {
+        if arg == "--" {
+            options_done = true;
+            continue;
+        }
+        // this would trigger both -n and -e in figlet
+        // but that would cause an error because
+        // -e is not a valid argument, so this won't
+        // break compatibility
+        if arg == "-ne" {
+            show_errors = false;
+            continue;
+        }
+
+        // flags can be combined, as in `-ctk`, and an option that takes a
+        // value uses the rest of the argument, as in `-w100`, or the next one
+        for (idx, flag) in arg.char_indices().skip(1) {
+            let rest = &arg[idx + flag.len_utf8()..];
+            let mut value = |name: &str| match rest {
+                "" => args_iter
+                    .next()
+                    .map(String::as_str)
+                    .unwrap_or_else(|| missing_arg(bin_name, name)),
+                rest => rest,
+            };
+
+            match flag {
                 // our own arguments
-                "e" => {
+                'e' => {
                     show_errors = true;
                 }
-                // this would trigger both -n and -e in figlet
-                // but that would cause an error because
-                // -e is not a valid argument, so this won't
-                // break compatibility
-                "ne" => {
-                    show_errors = false;
-                }
 
                 // figlet arguments
-                "X" => {
+                'X' => {
                     right_to_left = RightToLeft::Detect;
                 }
-                "L" => {
+                'L' => {
                     right_to_left = RightToLeft::Left;
                 }
-                "R" => {
+                'R' => {
                     right_to_left = RightToLeft::Right;
                 }
-                "x" => {
+                'x' => {
                     justification = Justification::Auto;
                 }
-                "l" => {
+                'l' => {
                     justification = Justification::Left;
                 }
-                "c" => {
+                'c' => {
                     justification = Justification::Center;
                 }
-                "r" => {
+                'r' => {
                     justification = Justification::Right;
                 }
-                "p" => {
+                'p' => {
                     paragraph = ParagraphMode::Paragraph;
                 }
-                "n" => {
+                'n' => {
                     paragraph = ParagraphMode::Normal;
                 }
-                "s" => {
+                's' => {
                     smush_override = SMO_NO;
                     smush_mode = None;
                 }
-                "k" => {
+                'k' => {
                     smush_mode = Some(SM_KERN);
                     smush_override = SMO_YES;
                 }
-                "S" => {
+                'S' => {
                     smush_mode = Some(SM_SMUSH);
                     smush_override = SMO_FORCE;
                 }
-                "o" => {
+                'o' => {
                     smush_mode = Some(SM_SMUSH);
                     smush_override = SMO_YES;
                 }
-                "W" => {
+                'W' => {
                     smush_mode = Some(SM_FULLWIDTH);
                     smush_override = SMO_YES;
                 }
-                "t" => {
+                't' => {
                     let term_size = termsize::get();
                     if let Some(term_size) = term_size {
                         width = term_size.cols;
@@ -160,15 +256,25 @@ pub fn from_args() -> FigletSettings {
                         error!("-t option requires a terminal");
                     }
                 }
-                "v" => {
+                'v' => {
                     infocode = 0;
                 }
-                "I" => {
-                    match args.get(x + 1).map(|s| s.parse()) {
-                        Some(Ok(next)) => {
+                // figlet's obsolete and control file options, which are
+                // accepted so scripts using them still run
+                'A' | 'D' | 'E' | 'F' | 'N' => {
+                    warn!("Ignoring unsupported option -{flag}");
+                }
+                'C' => {
+                    let control_file = value("-C");
+                    warn!("Ignoring control file {control_file}, they aren't supported");
+                    break;
+                }
+                'I' => {
+                    match value("-I").parse() {
+                        Ok(next) => {
                             infocode = next;
                         }
-                        Some(Err(err)) => {
+                        Err(err) => {
                             if show_errors {
                                 error!("Invalid argument for -I: {}", err);
                                 exit(1);
@@ -176,18 +282,15 @@ pub fn from_args() -> FigletSettings {
                                 infocode = 0;
                             }
                         }
-                        None => {
-                            missing_arg(bin_name, "-I");
-                        }
                     };
-                    consumed_next = true;
+                    break;
                 }
-                "m" => {
-                    match args.get(x + 1).map(|s| s.parse::<i32>()) {
-                        Some(Ok(mode)) => {
+                'm' => {
+                    match value("-m").parse::<i32>() {
+                        Ok(mode) => {
                             if mode < -1 {
                                 smush_override = SMO_NO;
-                                continue;
+                                break;
                             }
                             if mode == 0 {
                                 smush_mode = Some(SM_KERN);
@@ -198,7 +301,7 @@ pub fn from_args() -> FigletSettings {
                             }
                             smush_override = SMO_YES;
                         }
-                        Some(Err(err)) => {
+                        Err(err) => {
                             if show_errors {
                                 error!("Invalid argument for -m: {}", err);
                                 exit(1);
@@ -206,20 +309,17 @@ pub fn from_args() -> FigletSettings {
                                 smush_override = 0;
                             }
                         }
-                        None => {
-                            missing_arg(bin_name, "-m");
-                        }
                     };
-                    consumed_next = true;
+                    break;
                 }
-                "w" => {
-                    match args.get(x + 1).map(|s| s.parse::<u16>()) {
-                        Some(Ok(next)) => {
+                'w' => {
+                    match value("-w").parse::<u16>() {
+                        Ok(next) => {
                             if next > 0 {
                                 width = next;
                             }
                         }
-                        Some(Err(err)) => {
+                        Err(err) => {
                             if show_errors {
                                 error!("Invalid argument for -w: {}", err);
                                 exit(1);
@@ -227,49 +327,30 @@ pub fn from_args() -> FigletSettings {
                             // no else block because atoi defaults to 0
                             // and any values < 1 are ignored by figlet
                         }
-                        None => {
-                            missing_arg(bin_name, "-w");
-                        }
                     };
-                    consumed_next = true;
+                    break;
                 }
-                "d" => {
-                    let path = args.get(x + 1).map(PathBuf::from);
-                    if let Some(path) = path {
-                        font_directory = path;
-                    } else {
-                        missing_arg(bin_name, "-d");
-                    }
-                    consumed_next = true;
+                'd' => {
+                    font_directory = PathBuf::from(value("-d"));
+                    break;
                 }
-                "f" => {
-                    let font = args.get(x + 1);
-                    if let Some(font) = font {
-                        debug!("font: {font}");
-                        if font.ends_with(FIGLET_FONT_SUFFIX) {
-                            debug!("about to subtract suffix");
-                            font_name = font[0..font.len() - FIGLET_FONT_SUFFIX.len()].to_string();
-                        } else {
-                            font_name = font.to_string();
-                        }
+                'f' => {
+                    let font = value("-f");
+                    debug!("font: {font}");
+                    if font.ends_with(FIGLET_FONT_SUFFIX) {
+                        debug!("about to subtract suffix");
+                        font_name = font[0..font.len() - FIGLET_FONT_SUFFIX.len()].to_string();
                     } else {
-                        missing_arg(bin_name, "-f");
+                        font_name = font.to_string();
                     }
-                    consumed_next = true;
+                    break;
                 }
                 _ => {
-                    error!("Unknown argument: -{arg}");
+                    error!("Unknown argument: -{flag}");
                     print_usage(bin_name);
                     exit(1);
                 }
             }
-        } else if let Some(message) = &mut message {
-            // figlet adds all arguments to the message
-            // unless they start with '-'
-            message.push(' ');
-            message.push_str(arg);
-        } else {
-            message = Some(arg.to_string());
         }
     }
 
@@ -286,105 +367,3 @@ pub fn from_args() -> FigletSettings {
         message,
     }
 }
-
-#[derive(Debug, Parser)]
-#[command(author, version, about, long_about = None)]
-pub struct Args {
-    #[clap(short = 'f', long = "font", default_value = "default.frf")]
-    font: String,
-    #[clap(short = 'd', long = "font-dir", default_value_os_t = default_font_dir())]
-    font_directory: PathBuf,
-
-    #[clap(short = 'c', long = "justify-center", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    justify_center: bool,
-    #[clap(short = 'r', long = "justify-right", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    justify_right: bool,
-    #[clap(short = 'l', long = "justify-left", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    justify_left: bool,
-    #[clap(short = 'x', long = "justify-detect", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    justify_detect: bool,
-
-    #[clap(short = 't', long = "use-terminal-width", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    use_terminal_width: bool,
-    #[clap(short = 'w', long = "width", default_value = None)]
-    width: Option<usize>,
-
-    #[clap(short = 'p', long = "paragraph-mode", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    paragraph_mode: bool,
-    #[clap(short = 'n', long = "normal-mode", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    normal_mode: bool,
-
-    #[clap(short = 's', long = "smushing", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    smushing: bool,
-    #[clap(short = 'S', long = "force-smushing", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    force_smushing: bool,
-    #[clap(short = 'k', long = "kerning", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    kerning: bool,
-    #[clap(short = 'W', long = "full-width", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    full_width: bool,
-    #[clap(short = 'o', long = "overlap", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    overlap: bool,
-
-    #[clap(short = 'm', long = "layout-mode", default_value = None)]
-    layout_mode: Option<u8>,
-
-    #[clap(short = 'I', long = "info-code", default_value_t = -1)]
-    info_code: i8,
-
-    #[clap(short = 'L', long = "left-to-right", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    left_to_right: bool,
-    #[clap(short = 'R', long = "right-to-left", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    right_to_left: bool,
-    #[clap(short = 'X', long = "auto-direction", default_value_t = false, action = clap::ArgAction::SetTrue)]
-    auto_direction: bool,
-}
-
-// fn main() {
-//     env_logger::builder()
-//         .format_timestamp(None)
-//         .format_module_path(false)
-//         .format_target(false)
-//         .init();
-
-//     let args = Args::parse();
-
-//     let settings = match Settings::try_from(args) {
-//         Ok(settings) => settings,
-//         Err(e) => {
-//             error!("Invalid arguments: {e}");
-//             std::process::exit(1);
-//         }
-//     };
-
-//     if settings.info_code >= 0 {
-//         utils::print_info(&settings);
-//         exit(0);
-//     }
-
-//     let font_path = settings
-//         .font_directory
-//         .join(&settings.font)
-//         .with_extension("flf");
-
-//     let font = match FIGfont::from_file(&font_path) {
-//         Ok(font) => font,
-//         Err(err) => {
-//             error!("Failed to load font '{font_path:?}: {err}'");
-//             exit(1);
-//         }
-//     };
-
-//     let mut buf = String::new();
-//     if let Err(err) = std::io::stdin().read_to_string(&mut buf) {
-//         error!("Failed to read from stdin: {err}");
-//         exit(1);
-//     }
-
-//     match font.convert(buf, &settings) {
-//         Ok(output) => print!("{output}"),
-//         Err(err) => {
-//             error!("Failed to convert text: {err}");
-//             exit(1);
-//         }
-//     }
-// }
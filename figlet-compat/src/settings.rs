@@ -1,9 +1,11 @@
 use std::path::PathBuf;
 
-use figurust::settings::{Direction, Justify, Mode, Settings, Spacing};
-use thiserror::Error;
+use figurust::{
+    figlet::SearchPath,
+    settings::{Direction, Justify, MissingCharacterPolicy, Mode, Settings, Spacing},
+};
 
-use crate::{consts::Smushmode, Args};
+use crate::consts::*;
 
 // enumification of figlet `justification` variable
 #[derive(Debug)]
@@ -75,137 +77,61 @@ pub struct FigletSettings {
     pub message: Option<String>,
 }
 
-#[derive(Debug, Error)]
-pub enum SettingsError {
-    #[error("cannot set multiple {0} options")]
-    ConflictingOptions(&'static str),
-    #[error("Attempted to use --use-terminal-width outside of a terminal")]
-    NoTerminalSize,
-}
-
-fn assert_none_true(
-    name: &'static str,
-    vals: impl IntoIterator<Item = bool>,
-) -> Result<(), SettingsError> {
-    vals.into_iter()
-        .all(|x| !x)
-        .then_some(())
-        .ok_or(SettingsError::ConflictingOptions(name))
-}
-
-impl TryFrom<Args> for Settings {
-    type Error = SettingsError;
-    fn try_from(value: Args) -> Result<Self, Self::Error> {
-        let mut justify = Justify::default();
-        if value.justify_detect {
-            justify = Justify::Auto;
-            assert_none_true(
-                "justify",
-                [
-                    value.justify_left,
-                    value.justify_right,
-                    value.justify_center,
-                ],
-            )?;
-        } else if value.justify_center {
-            justify = Justify::Center;
-            assert_none_true(
-                "justify",
-                [
-                    value.justify_left,
-                    value.justify_right,
-                    value.justify_detect,
-                ],
-            )?;
-        } else if value.justify_right {
-            justify = Justify::Right;
-            assert_none_true(
-                "justify",
-                [
-                    value.justify_left,
-                    value.justify_center,
-                    value.justify_detect,
-                ],
-            )?;
-        } else if value.justify_left {
-            justify = Justify::Left;
-            assert_none_true(
-                "justify",
-                [
-                    value.justify_right,
-                    value.justify_center,
-                    value.justify_detect,
-                ],
-            )?;
+impl From<&FigletSettings> for Settings {
+    fn from(value: &FigletSettings) -> Self {
+        let justify = match value.justification {
+            Justification::Auto => Justify::Auto,
+            Justification::Left => Justify::Left,
+            Justification::Center => Justify::Center,
+            Justification::Right => Justify::Right,
+        };
+
+        let direction = match value.right_to_left {
+            RightToLeft::Detect => Direction::Auto,
+            RightToLeft::Left => Direction::LeftToRight,
+            RightToLeft::Right => Direction::RightToLeft,
+        };
+
+        let mode = match value.paragraph {
+            ParagraphMode::Normal => Mode::Normal,
+            ParagraphMode::Paragraph => Mode::Paragraph,
+        };
+
+        // figlet only looks at smush_mode when it's overriding the font
+        let mut spacing = Spacing::Smushing;
+        let mut overlap = false;
+        let mut layout_mode = None;
+        match (value.smush_override, value.smush_mode) {
+            (SMO_FORCE, _) => spacing = Spacing::ForceSmushing,
+            (SMO_YES, Some(SM_KERN)) => spacing = Spacing::Kerning,
+            (SMO_YES, Some(SM_FULLWIDTH)) => spacing = Spacing::FullWidth,
+            // smushing without any rules is figlet's universal smushing
+            (SMO_YES, Some(SM_SMUSH)) => overlap = true,
+            (SMO_YES, Some(mode)) => layout_mode = Some((mode & 63) as u8),
+            _ => {}
         }
 
-        let mut width = 80;
-        if value.use_terminal_width {
-            width = termsize::get()
-                .map(|dim| dim.cols as usize)
-                .ok_or(SettingsError::NoTerminalSize)?;
-            assert_none_true("width", [value.width.is_some()])?;
-        } else if let Some(w) = value.width {
-            width = w;
-            assert_none_true("width", [value.use_terminal_width])?;
-        }
+        let font = value
+            .font_name
+            .strip_suffix(FIGLET_FONT_SUFFIX)
+            .unwrap_or(&value.font_name);
 
-        let mut mode = Mode::default();
-        if value.paragraph_mode {
-            mode = Mode::Paragraph;
-            assert_none_true("mode", [value.normal_mode])?;
-        } else if value.normal_mode {
-            mode = Mode::Normal;
-            assert_none_true("mode", [value.paragraph_mode])?;
-        }
+        // fonts in the given directory take precedence over the usual ones
+        let mut font_path = SearchPath::default();
+        font_path.prepend(&value.font_directory);
 
-        let mut spacing = Spacing::default();
-        if value.smushing {
-            spacing = Spacing::Smushing;
-            assert_none_true(
-                "spacing",
-                [value.force_smushing, value.kerning, value.full_width],
-            )?;
-        } else if value.force_smushing {
-            spacing = Spacing::ForceSmushing;
-            assert_none_true("spacing", [value.smushing, value.kerning, value.full_width])?;
-        } else if value.kerning {
-            spacing = Spacing::Kerning;
-            assert_none_true(
-                "spacing",
-                [value.smushing, value.force_smushing, value.full_width],
-            )?;
-        } else if value.full_width {
-            spacing = Spacing::FullWidth;
-            assert_none_true(
-                "spacing",
-                [value.smushing, value.force_smushing, value.kerning],
-            )?;
-        }
-
-        let mut direction = Direction::default();
-        if value.auto_direction {
-            direction = Direction::Auto;
-            assert_none_true("direction", [value.left_to_right, value.right_to_left])?;
-        } else if value.left_to_right {
-            direction = Direction::LeftToRight;
-            assert_none_true("direction", [value.auto_direction, value.right_to_left])?;
-        } else if value.right_to_left {
-            direction = Direction::RightToLeft;
-            assert_none_true("direction", [value.auto_direction, value.left_to_right])?;
-        }
-
-        Ok(Self {
-            font: value.font,
-            font_directory: value.font_directory,
+        Self {
+            font: font.to_string(),
+            font_path,
             justify,
-            width,
+            width: Some(value.width as usize),
             mode,
             spacing,
-            overlap: value.overlap,
-            layout_mode: value.layout_mode,
-            info_code: value.info_code,
+            overlap,
+            layout_mode,
+            info_code: value.infocode,
             direction,
-        })
+            missing_character: MissingCharacterPolicy::CodeZero,
+        }
     }
 }
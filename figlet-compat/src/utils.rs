@@ -21,7 +21,9 @@ pub fn print_info(settings: &Settings) {
         }
         2 => {
             // font directory
-            println!("{}", settings.font_directory.display());
+            if let Some(directory) = settings.font_path.directories.first() {
+                println!("{}", directory.display());
+            }
         }
         3 => {
             // font name
@@ -29,7 +31,9 @@ pub fn print_info(settings: &Settings) {
         }
         4 => {
             // font width
-            println!("{}", settings.width);
+            if let Some(width) = settings.width {
+                println!("{width}");
+            }
         }
         5 => {
             // font format signature
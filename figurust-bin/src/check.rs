@@ -0,0 +1,53 @@
+use figurust::figlet::{validate_file, Severity};
+
+use crate::{
+    fonts::{font_files, FontFile},
+    CheckArgs,
+};
+
+/// Prints every problem in the fonts, returning whether they all passed
+pub fn check(args: &CheckArgs) -> bool {
+    let files = match font_files(&args.paths, true, &["flf", "tlf"]) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("Failed to find fonts: {e}");
+            return false;
+        }
+    };
+
+    let (mut errors, mut warnings, mut failed) = (0, 0, 0);
+    for FontFile { path: file, .. } in &files {
+        let diagnostics = match validate_file(file) {
+            Ok(diagnostics) => diagnostics,
+            Err(e) => {
+                eprintln!("Failed to read {}: {e}", file.display());
+                failed += 1;
+                continue;
+            }
+        };
+
+        let mut fails = false;
+        for diagnostic in &diagnostics {
+            println!("{}:{diagnostic}", file.display());
+            match diagnostic.severity() {
+                Severity::Error => {
+                    errors += 1;
+                    fails = true;
+                }
+                Severity::Warning => {
+                    warnings += 1;
+                    fails |= args.strict;
+                }
+            }
+        }
+        if fails {
+            failed += 1;
+        }
+    }
+
+    println!(
+        "checked {} fonts: {errors} errors, {warnings} warnings, {failed} failed",
+        files.len()
+    );
+    failed == 0
+}
@@ -0,0 +1,65 @@
+use std::{fs, io, path::Path};
+
+use thiserror::Error;
+
+use crate::{
+    fonts::{find_font, search_path, FindFontError},
+    CodegenArgs,
+};
+
+#[derive(Debug, Error)]
+pub enum CodegenError {
+    #[error("{0}")]
+    FindFontError(#[from] FindFontError),
+    #[error("IO Error: {0:?}")]
+    IoError(#[from] io::Error),
+    #[error("Not a valid Rust identifier: {0:?}")]
+    InvalidName(String),
+}
+
+/// Writes the font as a Rust module, which a crate can pull in with
+/// `include!` and turn into a font with `FIGfont::from`
+pub fn codegen(args: &CodegenArgs) -> Result<(), CodegenError> {
+    let font = find_font(&args.font, &search_path(&args.font_dirs))?;
+
+    let name = match &args.name {
+        Some(name) => name.clone(),
+        None => static_name(&args.font),
+    };
+    if !is_identifier(&name) {
+        return Err(CodegenError::InvalidName(name));
+    }
+
+    let source = font.to_rust(&name);
+    match &args.output {
+        Some(path) => fs::write(path, source)?,
+        None => print!("{source}"),
+    }
+    Ok(())
+}
+
+/// `fonts/ANSI Shadow.flf` becomes `ANSI_SHADOW`
+fn static_name(font: &str) -> String {
+    let stem = Path::new(font)
+        .file_stem()
+        .map_or(font.into(), |stem| stem.to_string_lossy());
+    let name: String = stem
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_uppercase(),
+            false => '_',
+        })
+        .collect();
+    match name.starts_with(|c: char| c.is_ascii_digit()) {
+        true => format!("FONT_{name}"),
+        false => name,
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
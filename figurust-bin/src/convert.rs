@@ -1,14 +1,20 @@
 use std::{
-    fs::OpenOptions,
-    io,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    num::NonZeroUsize,
     path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
 };
 
 use figurust::figlet::{FIGfont, FontLoadError};
 use ron::ser::PrettyConfig;
 use thiserror::Error;
 
-use crate::ConvertArgs;
+use crate::{
+    fonts::{font_files, FontFile},
+    ConvertArgs, FormatArg,
+};
 
 #[derive(Debug, Error)]
 pub enum ConvertFontError {
@@ -18,22 +24,159 @@ pub enum ConvertFontError {
     IoError(#[from] io::Error),
     #[error("Failed to serialize font: {0}")]
     SerializeError(#[from] ron::Error),
+    #[error("Failed to read RON font: {0}")]
+    RonError(#[from] ron::error::SpannedError),
+    #[error("Failed to read or write JSON font: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Can't tell the format from the extension, use --from")]
+    UnknownFormat,
+}
+
+const FORMATS: [FormatArg; 5] = [
+    FormatArg::Flf,
+    FormatArg::Tlf,
+    FormatArg::Ron,
+    FormatArg::Json,
+    FormatArg::Compiled,
+];
+
+impl FormatArg {
+    pub fn extension(self) -> &'static str {
+        match self {
+            FormatArg::Flf => "flf",
+            FormatArg::Tlf => "tlf",
+            FormatArg::Ron => "ron",
+            FormatArg::Json => "json",
+            FormatArg::Compiled => "figc",
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?;
+        FORMATS
+            .into_iter()
+            .find(|format| extension.eq_ignore_ascii_case(format.extension()))
+    }
+
+    pub fn read(self, path: &Path) -> Result<FIGfont, ConvertFontError> {
+        Ok(match self {
+            // toilet fonts only differ in their signature, which the parser
+            // accepts either way
+            FormatArg::Flf | FormatArg::Tlf => FIGfont::from_file(path)?,
+            FormatArg::Ron => ron::from_str(&fs::read_to_string(path)?)?,
+            FormatArg::Json => serde_json::from_str(&fs::read_to_string(path)?)?,
+            FormatArg::Compiled => FIGfont::from_compiled(&fs::read(path)?)?,
+        })
+    }
+
+    pub fn write(
+        self,
+        font: &FIGfont,
+        mut writer: impl Write,
+        pretty: bool,
+    ) -> Result<(), ConvertFontError> {
+        match self {
+            FormatArg::Flf => writer.write_all(font.to_flf().as_bytes())?,
+            FormatArg::Tlf => writer.write_all(font.to_tlf().as_bytes())?,
+            FormatArg::Ron if pretty => {
+                ron::ser::to_writer_pretty(writer, font, PrettyConfig::default())?
+            }
+            FormatArg::Ron => ron::ser::to_writer(writer, font)?,
+            FormatArg::Json if pretty => serde_json::to_writer_pretty(writer, font)?,
+            FormatArg::Json => serde_json::to_writer(writer, font)?,
+            FormatArg::Compiled => writer.write_all(&font.to_compiled())?,
+        }
+        Ok(())
+    }
 }
 
+/// Converts every font the arguments name, printing how each one went.
+/// Returns whether they all converted.
+pub fn convert_fonts(args: &ConvertArgs) -> bool {
+    let extensions: Vec<&str> = match args.from {
+        Some(format) => vec![format.extension()],
+        None => FORMATS.iter().map(|format| format.extension()).collect(),
+    };
+    let files = match font_files(&args.input_files, args.recursive, &extensions) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("Failed to find fonts: {e}");
+            return false;
+        }
+    };
+
+    let jobs = args
+        .jobs
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get))
+        .clamp(1, files.len().max(1));
+
+    // each worker takes the next file nobody has started on
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, Result<PathBuf, ConvertFontError>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+                    loop {
+                        let idx = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(file) = files.get(idx) else {
+                            break results;
+                        };
+                        results.push((idx, convert_font(file, args)));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("conversion thread panicked"))
+            .collect()
+    });
+    // reported in the order the files were given
+    results.sort_by_key(|(idx, _)| *idx);
+
+    let mut failed = 0;
+    for (idx, result) in &results {
+        let input = files[*idx].path.display();
+        match result {
+            Ok(output) => println!("{input} -> {}", output.display()),
+            Err(e) => {
+                eprintln!("{input}: {e}");
+                failed += 1;
+            }
+        }
+    }
+
+    println!(
+        "converted {} fonts, {failed} failed",
+        results.len() - failed
+    );
+    failed == 0
+}
+
+/// Converts one font, keeping its place relative to the directory it was
+/// found in
 pub fn convert_font(
+    FontFile { path, relative }: &FontFile,
     ConvertArgs {
-        input_file,
         output_dir,
         overwrite,
         pretty,
+        format,
+        from,
+        ..
     }: &ConvertArgs,
 ) -> Result<PathBuf, ConvertFontError> {
-    let font = FIGfont::from_file(input_file)?;
+    let input_format = from
+        .or_else(|| FormatArg::from_path(path))
+        .ok_or(ConvertFontError::UnknownFormat)?;
+    let font = input_format.read(path)?;
 
-    let mut output_file_path = output_dir.to_path_buf();
-    // SAFETY: if the font loaded, it has a file name
-    output_file_path.push(input_file.file_name().unwrap());
-    output_file_path.set_extension("ron");
+    let mut output_file_path = output_dir.join(relative);
+    output_file_path.set_extension(format.extension());
+    if let Some(parent) = output_file_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
 
     let mut open_opts = OpenOptions::new();
 
@@ -48,11 +191,9 @@ pub fn convert_font(
         .write(true)
         .open(&output_file_path)?;
 
-    if *pretty {
-        ron::ser::to_writer_pretty(output_file, &font, PrettyConfig::default())?;
-    } else {
-        ron::ser::to_writer(output_file, &font)?;
-    }
+    let mut writer = io::BufWriter::new(output_file);
+    format.write(&font, &mut writer, *pretty)?;
+    writer.flush()?;
 
     Ok(output_file_path)
 }
@@ -0,0 +1,134 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use figurust::figlet::{FIGfont, FontLoadError, RegistryError, SearchPath};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FindFontError {
+    #[error("Failed to load font file: {0}")]
+    FontLoadError(#[from] FontLoadError),
+    #[error("{0}")]
+    RegistryError(#[from] RegistryError),
+}
+
+/// The standard search path, with `--font-dir`s in front. The last
+/// directory given is searched first.
+pub fn search_path(font_dirs: &[PathBuf]) -> SearchPath {
+    let mut search_path = SearchPath::default();
+    for directory in font_dirs {
+        search_path.prepend(directory);
+    }
+    search_path
+}
+
+/// Loads `font` from a path if it's a file, otherwise looks it up by name
+pub fn find_font(font: &str, search_path: &SearchPath) -> Result<Arc<FIGfont>, FindFontError> {
+    if Path::new(font).is_file() {
+        return Ok(Arc::new(FIGfont::from_file(font)?));
+    }
+    Ok(search_path.registry().get(font)?)
+}
+
+/// A font file, along with where it goes relative to the directory it
+/// was found in
+#[derive(Debug, Clone)]
+pub struct FontFile {
+    pub path: PathBuf,
+    pub relative: PathBuf,
+}
+
+/// Every file in `paths`, with directories searched for files with one of
+/// `extensions` and glob patterns expanded. Subdirectories are only
+/// searched if `recursive` is set.
+pub fn font_files(
+    paths: &[PathBuf],
+    recursive: bool,
+    extensions: &[&str],
+) -> io::Result<Vec<FontFile>> {
+    let mut files = vec![];
+    for path in paths {
+        if path.is_dir() {
+            collect_fonts(path, path, recursive, extensions, &mut files)?;
+        } else if !path.exists() && is_pattern(path) {
+            let pattern = path.to_string_lossy();
+            let matches =
+                glob::glob(&pattern).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            // matches keep their place under the part of the pattern without
+            // wildcards, so `in/**/*.flf` can't send two fonts to one file
+            let base = literal_prefix(path);
+            for matched in matches {
+                let matched = matched.map_err(io::Error::from)?;
+                if matched.is_dir() {
+                    collect_fonts(&base, &matched, recursive, extensions, &mut files)?;
+                } else {
+                    let relative = match matched.strip_prefix(&base) {
+                        Ok(relative) => relative.to_path_buf(),
+                        Err(_) => FontFile::single(matched.clone()).relative,
+                    };
+                    files.push(FontFile {
+                        path: matched,
+                        relative,
+                    });
+                }
+            }
+        } else {
+            // missing files are left for whoever opens them to report
+            files.push(FontFile::single(path.clone()));
+        }
+    }
+    Ok(files)
+}
+
+impl FontFile {
+    fn single(path: PathBuf) -> Self {
+        let relative = path.file_name().map(PathBuf::from).unwrap_or_default();
+        Self { path, relative }
+    }
+}
+
+fn is_pattern(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?', '['])
+}
+
+/// The directories at the start of a glob pattern, before any wildcards
+fn literal_prefix(pattern: &Path) -> PathBuf {
+    pattern
+        .components()
+        .take_while(|component| !is_pattern(Path::new(component)))
+        .collect()
+}
+
+fn collect_fonts(
+    base: &Path,
+    directory: &Path,
+    recursive: bool,
+    extensions: &[&str],
+    files: &mut Vec<FontFile>,
+) -> io::Result<()> {
+    let mut entries = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    // sorted so output is the same on every filesystem
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            if recursive {
+                collect_fonts(base, &path, recursive, extensions, files)?;
+            }
+        } else if path.extension().is_some_and(|ext| {
+            extensions
+                .iter()
+                .any(|extension| ext.eq_ignore_ascii_case(extension))
+        }) {
+            // SAFETY: everything in the directory is under the base
+            let relative = path.strip_prefix(base).unwrap().to_path_buf();
+            files.push(FontFile { path, relative });
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use figurust::figlet::{layout::*, FIGfont};
+use thiserror::Error;
+
+use crate::{
+    fonts::{find_font, search_path, FindFontError},
+    InspectArgs,
+};
+
+#[derive(Debug, Error)]
+pub enum InspectError {
+    #[error("{0}")]
+    FindFontError(#[from] FindFontError),
+    #[error("Font has no glyph for {0}")]
+    MissingGlyph(String),
+}
+
+// the blocks fonts most often cover, anything else is counted as other
+const BLOCKS: &[(&str, u32, u32)] = &[
+    ("Basic Latin", 0x0000, 0x007f),
+    ("Latin-1 Supplement", 0x0080, 0x00ff),
+    ("Latin Extended-A", 0x0100, 0x017f),
+    ("Latin Extended-B", 0x0180, 0x024f),
+    ("Greek and Coptic", 0x0370, 0x03ff),
+    ("Cyrillic", 0x0400, 0x04ff),
+    ("Hebrew", 0x0590, 0x05ff),
+    ("Arabic", 0x0600, 0x06ff),
+    ("Hiragana", 0x3040, 0x309f),
+    ("Katakana", 0x30a0, 0x30ff),
+    ("General Punctuation", 0x2000, 0x206f),
+    ("Currency Symbols", 0x20a0, 0x20cf),
+    ("Arrows", 0x2190, 0x21ff),
+    ("Box Drawing", 0x2500, 0x257f),
+    ("Block Elements", 0x2580, 0x259f),
+];
+
+const HORIZONTAL_RULES: &[(LayoutMode, &str)] = &[
+    (SM_EQUAL, "equal character"),
+    (SM_LOWLINE, "underscore"),
+    (SM_HEIRARCHY, "hierarchy"),
+    (SM_PAIR, "opposite pair"),
+    (SM_BIGX, "big X"),
+    (SM_HARDBLANK, "hardblank"),
+];
+
+// the upper byte of full_layout, which figurust doesn't use yet
+const VERTICAL_RULES: &[(u16, &str)] = &[
+    (1 << 8, "equal character"),
+    (1 << 9, "underscore"),
+    (1 << 10, "hierarchy"),
+    (1 << 11, "horizontal line"),
+    (1 << 12, "vertical line"),
+];
+const VERTICAL_FITTING: u16 = 1 << 13;
+const VERTICAL_SMUSHING: u16 = 1 << 14;
+
+pub fn inspect(args: &InspectArgs) -> Result<(), InspectError> {
+    let font = find_font(&args.font, &search_path(&args.font_dirs))?;
+
+    match args.glyph {
+        Some(code) => print_glyph(&font, code),
+        None => {
+            print_header(&font);
+            print_coverage(&font);
+            Ok(())
+        }
+    }
+}
+
+fn print_header(font: &FIGfont) {
+    let header = &font.header;
+    println!("signature:      {}", header.signature);
+    println!("hardblank:      {:?}", header.hardblank);
+    println!("height:         {}", header.height);
+    println!("baseline:       {}", header.baseline);
+    println!("max length:     {}", header.max_length);
+    println!("horizontal:     {}", describe_horizontal(header.layout()));
+    match header.full_layout {
+        Some(full_layout) => println!("vertical:       {}", describe_vertical(full_layout)),
+        None => println!("vertical:       full height (no full_layout)"),
+    }
+    let direction = match header.direction {
+        None => "left to right (unspecified)",
+        Some(1) => "right to left",
+        Some(_) => "left to right",
+    };
+    println!("direction:      {direction}");
+    println!("comment lines:  {}", header.comment_lines);
+    if let Some(count) = header.codetag_count {
+        println!("code tags:      {count}");
+    }
+
+    println!();
+    for line in font.comments.lines() {
+        println!("  {line}");
+    }
+    println!();
+}
+
+fn describe_horizontal(mode: LayoutMode) -> String {
+    if mode & SM_SMUSH != 0 {
+        let rules = rule_names(mode, HORIZONTAL_RULES);
+        if rules.is_empty() {
+            "universal smushing".to_string()
+        } else {
+            format!("smushing ({})", rules.join(", "))
+        }
+    } else if mode & SM_KERN != 0 {
+        "kerning".to_string()
+    } else {
+        "full width".to_string()
+    }
+}
+
+fn describe_vertical(full_layout: u16) -> String {
+    let rules = rule_names(full_layout, VERTICAL_RULES);
+    if full_layout & VERTICAL_SMUSHING != 0 {
+        if rules.is_empty() {
+            "universal smushing".to_string()
+        } else {
+            format!("smushing ({})", rules.join(", "))
+        }
+    } else if full_layout & VERTICAL_FITTING != 0 {
+        "fitting".to_string()
+    } else {
+        "full height".to_string()
+    }
+}
+
+fn rule_names(mode: u16, rules: &[(u16, &'static str)]) -> Vec<&'static str> {
+    rules
+        .iter()
+        .filter(|(flag, _)| mode & flag != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+fn print_coverage(font: &FIGfont) {
+    let mut other = 0;
+    let mut counts = vec![0; BLOCKS.len()];
+    for &code in font.characters.keys() {
+        match BLOCKS
+            .iter()
+            .position(|&(_, start, end)| (start..=end).contains(&code))
+        {
+            Some(idx) => counts[idx] += 1,
+            None => other += 1,
+        }
+    }
+
+    println!("{} characters:", font.characters.len());
+    for (&(name, start, end), count) in BLOCKS.iter().zip(counts) {
+        if count > 0 {
+            println!("  {name:22} U+{start:04X}..U+{end:04X}  {count}");
+        }
+    }
+    if other > 0 {
+        println!("  {:22} {:14}  {other}", "Other", "");
+    }
+}
+
+fn print_glyph(font: &FIGfont, code: u32) -> Result<(), InspectError> {
+    let name = char::from_u32(code)
+        .filter(|c| !c.is_control())
+        .map_or_else(
+            || format!("U+{code:04X}"),
+            |c| format!("{c:?} U+{code:04X}"),
+        );
+    let character = font
+        .characters
+        .get(&code)
+        .ok_or_else(|| InspectError::MissingGlyph(name.clone()))?;
+
+    println!(
+        "{name}, {} columns, hardblank {:?}",
+        character.width, font.header.hardblank
+    );
+    let last = character.char_lines.len().saturating_sub(1);
+    for (idx, line) in character.char_lines.iter().enumerate() {
+        let line: String = line.iter().collect();
+        // fonts that weren't read from a font file don't have endmarks
+        let endmark = match character.endmarks.get(idx) {
+            Some(endmark) => endmark.as_str(),
+            None if idx == last => "@@",
+            None => "@",
+        };
+        // padded so trailing spaces show up before the endmark
+        println!("{line:width$}{endmark}", width = character.width);
+    }
+
+    Ok(())
+}
+
+pub fn parse_glyph(value: &str) -> Result<u32, String> {
+    let mut chars = value.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(c as u32);
+    }
+    value
+        .strip_prefix("U+")
+        .or_else(|| value.strip_prefix("u+"))
+        .or_else(|| value.strip_prefix("0x"))
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| format!("expected a character or a code like U+00E9, found {value:?}"))
+}
@@ -0,0 +1,93 @@
+use figurust::{
+    figlet::FIGfont,
+    settings::{MissingCharacterPolicy, Settings},
+};
+
+use crate::{fonts::search_path, ListFontsArgs};
+
+/// A font that passed the filters, ready to be printed
+struct Listing {
+    name: String,
+    location: String,
+    height: u16,
+    format: String,
+}
+
+pub fn list_fonts(args: &ListFontsArgs) {
+    let registry = search_path(&args.font_dirs).registry();
+
+    let settings = Settings {
+        width: Some(args.width),
+        // a preview is still useful without every character
+        missing_character: MissingCharacterPolicy::Skip,
+        ..Default::default()
+    };
+
+    let mut listings = vec![];
+    for name in registry.names() {
+        let font = match registry.get(&name) {
+            Ok(font) => font,
+            Err(e) => {
+                eprintln!("Skipping {name}: {e}");
+                continue;
+            }
+        };
+        if !matches_filters(&font, args) {
+            continue;
+        }
+
+        let location = registry.path(&name).map_or_else(
+            || "(bundled)".to_string(),
+            |path| path.display().to_string(),
+        );
+        let listing = Listing {
+            name,
+            location,
+            height: font.header.height,
+            format: font.header.signature.clone(),
+        };
+        listings.push((listing, font));
+    }
+
+    let name_width = listings
+        .iter()
+        .map(|(listing, _)| listing.name.len())
+        .max()
+        .unwrap_or(0);
+    let location_width = listings
+        .iter()
+        .map(|(listing, _)| listing.location.len())
+        .max()
+        .unwrap_or(0);
+
+    for (listing, font) in &listings {
+        println!(
+            "{:name_width$}  {:location_width$}  height {:<3} {}",
+            listing.name, listing.location, listing.height, listing.format
+        );
+
+        if args.preview || args.sample.is_some() {
+            let sample = args.sample.as_deref().unwrap_or(&listing.name);
+            match font.convert(sample, &settings) {
+                Ok(preview) => println!("{preview}"),
+                Err(e) => eprintln!("Failed to preview {}: {e}", listing.name),
+            }
+        }
+    }
+}
+
+fn matches_filters(font: &FIGfont, args: &ListFontsArgs) -> bool {
+    let height = font.header.height;
+    if args.min_height.is_some_and(|min| height < min) {
+        return false;
+    }
+    if args.max_height.is_some_and(|max| height > max) {
+        return false;
+    }
+    match &args.covers {
+        Some(covers) => covers
+            .chars()
+            .all(|c| font.characters.contains_key(&(c as u32))),
+        None => true,
+    }
+}
@@ -1,9 +1,16 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, process::exit};
 
-use clap::{Args, Parser, Subcommand};
-use log::{debug, error, info};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use figurust::style::Color;
+use log::debug;
 
+pub mod check;
+pub mod codegen;
 pub mod convert;
+pub mod fonts;
+pub mod inspect;
+pub mod list;
+pub mod render;
 
 #[derive(Debug, Parser)]
 #[clap(author, version, about, long_about = None)]
@@ -14,22 +21,194 @@ pub struct Cli {
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
-    /// Convert a .flf font to a .ron font
+    /// Convert fonts between formats
     Convert(ConvertArgs),
+    /// Render text with a FIGlet font
+    Render(RenderArgs),
+    /// List the fonts on the search path
+    ListFonts(ListFontsArgs),
+    /// Show a font's header, comments and character coverage
+    Inspect(InspectArgs),
+    /// Check font files for problems, like figlet's chkfont
+    Check(CheckArgs),
+    /// Write a font as Rust source, to embed it without parsing at runtime
+    Codegen(CodegenArgs),
 }
 
 #[derive(Debug, Args)]
 pub struct ConvertArgs {
-    #[clap(short = 'i', long = "input-file")]
-    input_file: PathBuf,
+    /// Font files, glob patterns, or directories to search for fonts
+    #[clap(short = 'i', long = "input-file", required = true, num_args = 1..)]
+    input_files: Vec<PathBuf>,
+    /// Search directories' subdirectories too, keeping their structure
+    /// under the output directory
+    #[clap(short = 'r', long = "recursive", default_value_t = false, action = clap::ArgAction::SetTrue)]
+    recursive: bool,
+    /// How many fonts to convert at once, defaults to one per core
+    #[clap(short = 'j', long = "jobs")]
+    jobs: Option<usize>,
     #[clap(short = 'o', long = "output-dir", default_value_os_t = PathBuf::from("."))]
     output_dir: PathBuf,
+    /// The format to write
+    #[clap(short = 'F', long = "format", value_enum, default_value_t = FormatArg::Ron)]
+    format: FormatArg,
+    /// The format to read, worked out from each file's extension by default
+    #[clap(long = "from", value_enum)]
+    from: Option<FormatArg>,
     #[clap(short = 'f', long = "force", default_value_t = false, action = clap::ArgAction::SetTrue)]
     overwrite: bool,
     #[clap(short = 'p', long = "pretty", default_value_t = false, action = clap::ArgAction::SetTrue)]
     pretty: bool,
 }
 
+#[derive(Debug, Args)]
+pub struct RenderArgs {
+    /// The text to render, read from --input or stdin if there is none
+    text: Vec<String>,
+    /// Read the text from a file, - for stdin
+    #[clap(short = 'i', long = "input", conflicts_with = "text")]
+    input: Option<PathBuf>,
+    /// A font name to look up, or the path to a font file
+    #[clap(short = 'f', long = "font")]
+    font: Option<String>,
+    /// Look for fonts here before anywhere else, can be given more than once
+    #[clap(short = 'd', long = "font-dir")]
+    font_dirs: Vec<PathBuf>,
+    #[clap(long = "spacing", value_enum, default_value_t = SpacingArg::Smush)]
+    spacing: SpacingArg,
+    /// Overlap every character by one column
+    #[clap(long = "overlap", default_value_t = false, action = clap::ArgAction::SetTrue)]
+    overlap: bool,
+    /// Smush with these figlet layout rules instead of the font's
+    #[clap(short = 'm', long = "layout-mode")]
+    layout_mode: Option<u8>,
+    #[clap(short = 'j', long = "justify", value_enum, default_value_t = JustifyArg::Auto)]
+    justify: JustifyArg,
+    /// Wrap lines to this many columns, 0 to never wrap
+    #[clap(short = 'w', long = "width", default_value_t = 80)]
+    width: usize,
+    #[clap(long = "direction", value_enum, default_value_t = DirectionArg::Auto)]
+    direction: DirectionArg,
+    /// Treat single line breaks as spaces
+    #[clap(short = 'p', long = "paragraph", default_value_t = false, action = clap::ArgAction::SetTrue)]
+    paragraph: bool,
+    /// What to do with characters the font doesn't have: error, skip,
+    /// code-zero, or a character to draw instead
+    #[clap(long = "missing", default_value = "error", value_parser = render::parse_missing)]
+    missing: figurust::settings::MissingCharacterPolicy,
+    /// Write to a file instead of stdout, .png, .svg and .html are
+    /// rendered as images
+    #[clap(short = 'o', long = "output")]
+    output: Option<PathBuf>,
+    /// A color name or #rrggbb
+    #[clap(short = 'c', long = "color")]
+    color: Option<Color>,
+    #[clap(short = 'b', long = "background")]
+    background: Option<Color>,
+    /// Pixel scale of .png output
+    #[clap(short = 's', long = "scale", default_value_t = 1)]
+    scale: u32,
+}
+
+#[derive(Debug, Args)]
+pub struct ListFontsArgs {
+    /// Look for fonts here before anywhere else, can be given more than once
+    #[clap(short = 'd', long = "font-dir")]
+    font_dirs: Vec<PathBuf>,
+    /// Show each font's name rendered in itself
+    #[clap(short = 'p', long = "preview", default_value_t = false, action = clap::ArgAction::SetTrue)]
+    preview: bool,
+    /// Preview this text instead of the font's name
+    #[clap(short = 's', long = "sample")]
+    sample: Option<String>,
+    #[clap(long = "min-height")]
+    min_height: Option<u16>,
+    #[clap(long = "max-height")]
+    max_height: Option<u16>,
+    /// Only list fonts that have every one of these characters
+    #[clap(long = "covers")]
+    covers: Option<String>,
+    /// Wrap previews to this many columns
+    #[clap(short = 'w', long = "width", default_value_t = 80)]
+    width: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct InspectArgs {
+    /// A font name to look up, or the path to a font file
+    font: String,
+    /// Look for fonts here before anywhere else, can be given more than once
+    #[clap(short = 'd', long = "font-dir")]
+    font_dirs: Vec<PathBuf>,
+    /// Dump one glyph instead, given as a character or a code like U+00E9
+    #[clap(short = 'g', long = "glyph", value_parser = inspect::parse_glyph)]
+    glyph: Option<u32>,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckArgs {
+    /// Font files, or directories to search for them
+    #[clap(required = true)]
+    paths: Vec<PathBuf>,
+    /// Fail on warnings as well as errors
+    #[clap(short = 'W', long = "strict", default_value_t = false, action = clap::ArgAction::SetTrue)]
+    strict: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct CodegenArgs {
+    /// A font name to look up, or the path to a font file
+    font: String,
+    /// Look for fonts here before anywhere else, can be given more than once
+    #[clap(short = 'd', long = "font-dir")]
+    font_dirs: Vec<PathBuf>,
+    /// The name of the static, the font's name in capitals by default
+    #[clap(short = 'n', long = "name")]
+    name: Option<String>,
+    /// Write to a file instead of stdout
+    #[clap(short = 'o', long = "output")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FormatArg {
+    /// figlet's text format
+    Flf,
+    /// toilet's text format
+    Tlf,
+    Ron,
+    Json,
+    /// figurust's binary format, the quickest to load
+    Compiled,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SpacingArg {
+    /// Use the font's own layout
+    Smush,
+    /// Smush even if the font doesn't ask for it
+    ForceSmush,
+    Kern,
+    FullWidth,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum JustifyArg {
+    /// Left, or right for right-to-left text
+    Auto,
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum DirectionArg {
+    /// Use the font's direction
+    Auto,
+    Ltr,
+    Rtl,
+}
+
 fn main() {
     env_logger::init();
 
@@ -37,14 +216,35 @@ fn main() {
 
     if let Some(command) = &args.command {
         match command {
-            Commands::Convert(convert) => match convert::convert_font(convert) {
-                Ok(path) => info!(
-                    "Converted font: {} -> {}",
-                    convert.input_file.display(),
-                    path.display()
-                ),
-                Err(e) => error!("Failed to convert font: {}", e),
-            },
+            Commands::Convert(convert) => {
+                if !convert::convert_fonts(convert) {
+                    exit(1);
+                }
+            }
+            Commands::Render(render) => {
+                if let Err(e) = render::render(render) {
+                    eprintln!("Failed to render text: {e}");
+                    exit(1);
+                }
+            }
+            Commands::ListFonts(list) => list::list_fonts(list),
+            Commands::Check(check) => {
+                if !check::check(check) {
+                    exit(1);
+                }
+            }
+            Commands::Codegen(codegen) => {
+                if let Err(e) = codegen::codegen(codegen) {
+                    eprintln!("Failed to generate font source: {e}");
+                    exit(1);
+                }
+            }
+            Commands::Inspect(inspect) => {
+                if let Err(e) = inspect::inspect(inspect) {
+                    eprintln!("Failed to inspect font: {e}");
+                    exit(1);
+                }
+            }
         }
     }
 
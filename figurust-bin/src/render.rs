@@ -0,0 +1,168 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::Path,
+};
+
+use figurust::{
+    figlet::FontConvertError,
+    output::{to_html, to_png, to_svg, HtmlSettings, PngError, PngSettings, SvgSettings},
+    settings::{Direction, Justify, MissingCharacterPolicy, Mode, Settings, Spacing},
+    style::{ColorSupport, Style},
+};
+use thiserror::Error;
+
+use crate::{
+    fonts::{find_font, search_path, FindFontError},
+    DirectionArg, JustifyArg, RenderArgs, SpacingArg,
+};
+
+#[derive(Debug, Error)]
+pub enum RenderError {
+    #[error("{0}")]
+    FindFontError(#[from] FindFontError),
+    #[error("{0}")]
+    FontConvertError(#[from] FontConvertError),
+    #[error("IO Error: {0:?}")]
+    IoError(#[from] io::Error),
+    #[error("{0}")]
+    PngError(#[from] PngError),
+}
+
+pub fn render(args: &RenderArgs) -> Result<(), RenderError> {
+    let RenderArgs {
+        output,
+        color,
+        background,
+        scale,
+        ..
+    } = args;
+
+    let settings = settings(args);
+    let font = find_font(&settings.font, &settings.font_path)?;
+    let text = read_text(args)?;
+    let figure = font.render(text, &settings)?;
+    let style = color.map(Style::solid);
+
+    let Some(output) = output else {
+        match &style {
+            Some(style) => print!("{}", style.paint(&figure, ColorSupport::detect())),
+            None => print!("{figure}"),
+        }
+        return Ok(());
+    };
+
+    match extension(output).as_deref() {
+        Some("png") => {
+            let mut settings = PngSettings {
+                scale: *scale,
+                style,
+                ..Default::default()
+            };
+            if background.is_some() {
+                settings.background = *background;
+            }
+            fs::write(output, to_png(&figure, &settings)?)?;
+        }
+        Some("svg") => {
+            let settings = SvgSettings {
+                background: *background,
+                style,
+                ..Default::default()
+            };
+            fs::write(output, to_svg(&figure, &settings))?;
+        }
+        Some("html" | "htm") => {
+            let settings = HtmlSettings {
+                style,
+                ..Default::default()
+            };
+            fs::write(output, to_html(&figure, &settings))?;
+        }
+        _ => fs::write(output, figure.to_string())?,
+    }
+
+    Ok(())
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+}
+
+fn settings(args: &RenderArgs) -> Settings {
+    let mut settings = Settings {
+        font_path: search_path(&args.font_dirs),
+        spacing: match args.spacing {
+            SpacingArg::Smush => Spacing::Smushing,
+            SpacingArg::ForceSmush => Spacing::ForceSmushing,
+            SpacingArg::Kern => Spacing::Kerning,
+            SpacingArg::FullWidth => Spacing::FullWidth,
+        },
+        overlap: args.overlap,
+        layout_mode: args.layout_mode,
+        justify: match args.justify {
+            JustifyArg::Auto => Justify::Auto,
+            JustifyArg::Left => Justify::Left,
+            JustifyArg::Center => Justify::Center,
+            JustifyArg::Right => Justify::Right,
+        },
+        width: (args.width > 0).then_some(args.width),
+        direction: match args.direction {
+            DirectionArg::Auto => Direction::Auto,
+            DirectionArg::Ltr => Direction::LeftToRight,
+            DirectionArg::Rtl => Direction::RightToLeft,
+        },
+        mode: if args.paragraph {
+            Mode::Paragraph
+        } else {
+            Mode::Normal
+        },
+        missing_character: args.missing,
+        ..Default::default()
+    };
+    if let Some(font) = &args.font {
+        settings.font = font.clone();
+    }
+    settings
+}
+
+fn read_text(args: &RenderArgs) -> Result<String, RenderError> {
+    if !args.text.is_empty() {
+        return Ok(args.text.join(" "));
+    }
+
+    let mut text = match &args.input {
+        Some(path) if path != Path::new("-") => fs::read_to_string(path)?,
+        _ => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            text
+        }
+    };
+    // the final line break would otherwise be a blank line
+    if text.ends_with('\n') {
+        text.pop();
+        if text.ends_with('\r') {
+            text.pop();
+        }
+    }
+    Ok(text)
+}
+
+pub fn parse_missing(value: &str) -> Result<MissingCharacterPolicy, String> {
+    match value {
+        "error" => Ok(MissingCharacterPolicy::Error),
+        "skip" => Ok(MissingCharacterPolicy::Skip),
+        "code-zero" => Ok(MissingCharacterPolicy::CodeZero),
+        _ => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(MissingCharacterPolicy::Replace(c)),
+                _ => Err(format!(
+                    "expected error, skip, code-zero or a single character, found {value:?}"
+                )),
+            }
+        }
+    }
+}
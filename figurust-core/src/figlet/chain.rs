@@ -0,0 +1,310 @@
+use crate::{
+    figure::{Cell, FIGure, Substitution},
+    settings::{MissingCharacterPolicy, Mode, Settings},
+};
+
+use super::{
+    layout::{Extent, Glyph, LineLayout, PairCache},
+    FIGfont, FontConvertError, Measurement,
+};
+
+/// An ordered list of fonts to render with
+///
+/// Characters missing from the first font are taken from the next one that
+/// has them. Glyphs from fonts of different heights are lined up on their
+/// baselines.
+#[derive(Debug, Clone)]
+pub struct FontChain<'a> {
+    fonts: Vec<&'a FIGfont>,
+}
+
+pub(crate) enum Lookup<'a> {
+    Found(Glyph<'a>),
+    /// the character was missing, this one is drawn instead
+    Substituted(Glyph<'a>, char),
+    Skipped,
+}
+
+impl<'a> FontChain<'a> {
+    pub fn new(font: &'a FIGfont) -> Self {
+        Self { fonts: vec![font] }
+    }
+
+    /// Adds a font to take missing characters from, after every font
+    /// already in the chain
+    pub fn with_fallback(mut self, font: &'a FIGfont) -> Self {
+        self.fonts.push(font);
+        self
+    }
+
+    pub fn fonts(&self) -> &[&'a FIGfont] {
+        &self.fonts
+    }
+
+    pub fn primary(&self) -> &'a FIGfont {
+        self.fonts[0]
+    }
+
+    /// The first font in the chain with a character for `code`
+    fn find(&self, code: u32) -> Option<Glyph<'a>> {
+        self.fonts.iter().find_map(|&font| {
+            let character = font.characters.get(&code)?;
+            Some(Glyph { font, character })
+        })
+    }
+
+    /// Finds the glyph to draw for `c`, following the missing character
+    /// policy if no font has it
+    pub(crate) fn lookup(
+        &self,
+        c: char,
+        policy: MissingCharacterPolicy,
+    ) -> Result<Lookup<'a>, FontConvertError> {
+        if let Some(glyph) = self.find(c as u32) {
+            return Ok(Lookup::Found(glyph));
+        }
+
+        match policy {
+            MissingCharacterPolicy::Error => Err(FontConvertError::MissingCharacter(c)),
+            MissingCharacterPolicy::Skip => Ok(Lookup::Skipped),
+            // figlet quietly drops the character if there's no code 0 either
+            MissingCharacterPolicy::CodeZero => Ok(self
+                .find(0)
+                .map_or(Lookup::Skipped, |glyph| Lookup::Substituted(glyph, '\0'))),
+            MissingCharacterPolicy::Replace(replacement) => self
+                .find(replacement as u32)
+                .map(|glyph| Lookup::Substituted(glyph, replacement))
+                .ok_or(FontConvertError::MissingCharacter(replacement)),
+        }
+    }
+
+    pub fn convert(
+        &self,
+        content: impl AsRef<str>,
+        settings: &Settings,
+    ) -> Result<String, FontConvertError> {
+        Ok(self.render(content, settings)?.to_string())
+    }
+
+    /// Lays out `content` into a grid of cells, one line of glyphs per
+    /// input line
+    pub fn render(
+        &self,
+        content: impl AsRef<str>,
+        settings: &Settings,
+    ) -> Result<FIGure, FontConvertError> {
+        let content = content.as_ref();
+        let layout = lay_out(content, settings, true, |_| self)?;
+        Ok(FIGure::from_rows(content, layout.rows).with_substitutions(layout.substitutions))
+    }
+
+    /// Works out the size `render` would produce without building the output
+    pub fn measure(
+        &self,
+        content: impl AsRef<str>,
+        settings: &Settings,
+    ) -> Result<Measurement, FontConvertError> {
+        Ok(lay_out(content.as_ref(), settings, false, |_| self)?.measurement)
+    }
+}
+
+pub(crate) struct Layout {
+    pub rows: Vec<Vec<Cell>>,
+    pub substitutions: Vec<Substitution>,
+    pub measurement: Measurement,
+}
+
+/// A glyph along with the input character it draws
+#[derive(Debug, Clone, Copy)]
+struct Placed<'a> {
+    source: usize,
+    c: char,
+    glyph: Glyph<'a>,
+}
+
+/// Lays out every line of `content`, drawing each character with the chain
+/// `chain_at` picks for its byte offset. Only the size is worked out unless
+/// `draw` is set.
+pub(crate) fn lay_out<'a, 'c>(
+    content: &str,
+    settings: &Settings,
+    draw: bool,
+    chain_at: impl Fn(usize) -> &'c FontChain<'a>,
+) -> Result<Layout, FontConvertError>
+where
+    'a: 'c,
+{
+    let mut layout = Layout {
+        rows: vec![],
+        substitutions: vec![],
+        measurement: Measurement {
+            width: 0,
+            height: 0,
+        },
+    };
+
+    let mut cache = PairCache::default();
+    let paragraph = matches!(settings.mode, Mode::Paragraph);
+    for (offset, line) in logical_lines(content, paragraph) {
+        let mut glyphs = vec![];
+        for (source, c) in line {
+            match chain_at(source).lookup(c, settings.missing_character)? {
+                Lookup::Found(glyph) => glyphs.push(Placed { source, c, glyph }),
+                Lookup::Substituted(glyph, replacement) => {
+                    layout.substitutions.push(Substitution {
+                        offset: source,
+                        original: c,
+                        replacement: Some(replacement),
+                    });
+                    glyphs.push(Placed { source, c, glyph });
+                }
+                Lookup::Skipped => layout.substitutions.push(Substitution {
+                    offset: source,
+                    original: c,
+                    replacement: None,
+                }),
+            }
+        }
+
+        let primary = chain_at(offset).primary();
+        let right_to_left = settings.right_to_left(&primary.header);
+        for mut glyphs in wrap(glyphs, settings, &mut cache) {
+            // a line is only as tall as the fonts it actually uses
+            let extent = glyphs
+                .iter()
+                .map(|placed| Extent::of(placed.glyph.font))
+                .reduce(Extent::union)
+                .unwrap_or_else(|| Extent::of(primary));
+
+            if right_to_left {
+                glyphs.reverse();
+            }
+            let mut line_layout = LineLayout::new(extent, draw, right_to_left);
+            for placed in glyphs {
+                line_layout.push(placed.glyph, placed.source, settings, &mut cache);
+            }
+
+            let width = line_layout.width();
+            let padding = settings.justify_padding(width, right_to_left);
+            layout.measurement.width = layout.measurement.width.max(padding + width);
+            layout.measurement.height += line_layout.height();
+            layout.rows.append(&mut line_layout.into_rows(padding));
+        }
+    }
+
+    Ok(layout)
+}
+
+/// Splits a line of glyphs into lines that fit the width in `settings`,
+/// breaking at the last space that fits like figlet does. Words too long
+/// for a line of their own are broken anywhere.
+fn wrap<'a>(
+    glyphs: Vec<Placed<'a>>,
+    settings: &Settings,
+    cache: &mut PairCache,
+) -> Vec<Vec<Placed<'a>>> {
+    let Some(limit) = settings.line_limit() else {
+        return vec![glyphs];
+    };
+
+    let extent = glyphs
+        .iter()
+        .map(|placed| Extent::of(placed.glyph.font))
+        .reduce(Extent::union)
+        .unwrap_or_default();
+    let measure = |glyphs: &[Placed], cache: &mut PairCache| {
+        // only the width matters here, which doesn't depend on direction
+        let mut line_layout = LineLayout::new(extent, false, false);
+        for placed in glyphs {
+            line_layout.push(placed.glyph, placed.source, settings, cache);
+        }
+        line_layout
+    };
+
+    let mut lines = vec![];
+    let mut line = vec![];
+    let mut line_layout = measure(&[], cache);
+    for placed in glyphs {
+        line_layout.push(placed.glyph, placed.source, settings, cache);
+        line.push(placed);
+
+        while line_layout.width() > limit && line.len() > 1 {
+            let rest = match line.iter().rposition(|placed| placed.c == ' ') {
+                // the space itself is dropped
+                Some(idx) if idx > 0 => {
+                    let rest = line.split_off(idx + 1);
+                    line.pop();
+                    rest
+                }
+                _ => line.split_off(line.len() - 1),
+            };
+            lines.push(std::mem::replace(&mut line, rest));
+            line_layout = measure(&line, cache);
+        }
+    }
+    lines.push(line);
+
+    lines
+}
+
+/// The characters of each line of input along with their byte offsets,
+/// and the offset each line starts at.
+///
+/// In paragraph mode a line break is just a space, unless it follows
+/// another break or the next line starts with whitespace, like figlet.
+fn logical_lines(content: &str, paragraph: bool) -> Vec<(usize, Vec<(usize, char)>)> {
+    let mut lines: Vec<(usize, Vec<(usize, char)>)> = vec![];
+    for (offset, line) in input_lines(content) {
+        let chars = input_chars(line).map(|(idx, c)| (offset + idx, c));
+
+        let joins = paragraph
+            && !line.is_empty()
+            && !line.starts_with(char::is_whitespace)
+            && lines.last().is_some_and(|(_, prev)| !prev.is_empty());
+        match lines.last_mut() {
+            Some((_, prev)) if joins => {
+                // the break before this line is drawn as the space
+                prev.push((offset - 1, ' '));
+                prev.extend(chars);
+            }
+            _ => lines.push((offset, chars.collect())),
+        }
+    }
+    lines
+}
+
+// figlet treats every other kind of ascii whitespace as a line break
+const LINE_BREAKS: [char; 4] = ['\n', '\r', '\x0b', '\x0c'];
+
+/// Splits input into lines, along with the byte offset each line starts at
+fn input_lines(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut rest = content;
+    let mut line_offset = 0;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let (line, consumed) = match rest.find(LINE_BREAKS) {
+            // windows line endings are a single break
+            Some(idx) if rest[idx..].starts_with("\r\n") => (&rest[..idx], idx + 2),
+            Some(idx) => (&rest[..idx], idx + 1),
+            None => (rest, rest.len()),
+        };
+
+        let offset = line_offset;
+        line_offset += consumed;
+        rest = &rest[consumed..];
+        Some((offset, line))
+    })
+}
+
+/// The characters of a line as figlet would see them: tabs become spaces
+/// and any other control characters are dropped
+fn input_chars(line: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    line.char_indices().filter_map(|(idx, c)| match c {
+        '\t' => Some((idx, ' ')),
+        c if c.is_ascii_control() => None,
+        c => Some((idx, c)),
+    })
+}
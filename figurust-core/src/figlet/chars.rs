@@ -0,0 +1,252 @@
+use serde::{Deserialize, Deserializer, Serializer};
+use thiserror::Error;
+
+use super::layout::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "StoredCharacter")]
+pub struct FIGcharacter {
+    /// one `Vec` of sub-characters per row, so art outside of ascii can be
+    /// indexed by column
+    #[serde(with = "char_grid")]
+    pub char_lines: Vec<Vec<char>>,
+    #[serde(skip)]
+    pub width: usize,
+    /// the edges of each row, worked out once so the layout never has to
+    /// scan the rows themselves
+    #[serde(skip)]
+    pub profile: Vec<RowProfile>,
+    /// what each row ended with in the font file, empty if the character
+    /// wasn't read from one
+    #[serde(skip)]
+    pub endmarks: Vec<String>,
+}
+
+/// Where the art on one row of a character starts and ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RowProfile {
+    /// blank columns before the first sub-character, the whole row if it's blank
+    pub left: usize,
+    /// the leftmost non-blank sub-character
+    pub first: Option<char>,
+    /// the rightmost non-blank column and its sub-character
+    pub last: Option<(usize, char)>,
+}
+
+impl RowProfile {
+    pub fn of(line: &[char]) -> Self {
+        let left = line.iter().take_while(|&&ch| ch == ' ').count();
+        let last = line
+            .iter()
+            .rposition(|&ch| ch != ' ')
+            .map(|idx| (idx, line[idx]));
+        Self {
+            left,
+            first: line.get(left).copied(),
+            last,
+        }
+    }
+}
+
+// the width and profile are derived from the rows, so they aren't stored
+// with the font. fonts saved with a width still load, it's just ignored.
+#[derive(Deserialize)]
+struct StoredCharacter {
+    #[serde(with = "char_grid")]
+    char_lines: Vec<Vec<char>>,
+}
+
+impl From<StoredCharacter> for FIGcharacter {
+    fn from(stored: StoredCharacter) -> Self {
+        Self::new(stored.char_lines)
+    }
+}
+
+// fonts are stored with each row as a string, which is much easier to read
+// than a list of single characters
+mod char_grid {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(lines: &[Vec<char>], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(lines.iter().map(|line| line.iter().collect::<String>()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<char>>, D::Error> {
+        let lines = Vec::<String>::deserialize(deserializer)?;
+        Ok(lines.iter().map(|line| line.chars().collect()).collect())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CharacterParseError {
+    #[error("Temp")]
+    _Temp,
+}
+
+// i am envisioning a much less readable version of this
+// but this uses a small amount of memory and is easy to read
+
+const TOP_CMP: &str = "_";
+const TOP_HEIRARCHY: &str = "|/\\[]{}()<>";
+
+const BARLESS_CMP: &str = "|";
+const BARLESS_HEIRARCHY: &str = "/\\[]{}()<>";
+
+const SLASHLESS_CMP: &str = "/\\";
+const SLASHLESS_HEIRARCHY: &str = "[]{}()<>";
+
+const BRACKETLESS_CMP: &str = "[]";
+const BRACKETLESS_HEIRARCHY: &str = "{}()<>";
+
+const CURLYLESS_CMP: &str = "{}";
+const CURLYLESS_HEIRARCHY: &str = "()<>";
+
+const PARENLESS_CMP: &str = "()";
+const PARENLESS_HEIRARCHY: &str = "<>";
+
+impl FIGcharacter {
+    pub fn from_lines(lines: &mut [&str]) -> Result<Self, CharacterParseError> {
+        let mut char_lines = vec![];
+        let mut endmarks = vec![];
+        for line in lines.iter() {
+            // like figlet, the endmark is whatever the line ends with, and
+            // every trailing copy of it is removed
+            let line = line.trim_end();
+            let mut chars: Vec<char> = line.chars().collect();
+            let mut endmark = String::new();
+            if let Some(&last) = chars.last() {
+                while chars.last() == Some(&last) {
+                    endmark.push(last);
+                    chars.pop();
+                }
+            }
+
+            char_lines.push(chars);
+            endmarks.push(endmark);
+        }
+        Ok(Self {
+            endmarks,
+            ..Self::new(char_lines)
+        })
+    }
+
+    pub fn new(char_lines: Vec<Vec<char>>) -> Self {
+        let width = char_lines.iter().map(Vec::len).max().unwrap_or(0);
+        let profile = char_lines.iter().map(|line| RowProfile::of(line)).collect();
+        Self {
+            char_lines,
+            width,
+            profile,
+            endmarks: vec![],
+        }
+    }
+}
+
+/// Smushes two sub-characters together following figlet's horizontal rules.
+///
+/// `widths` holds the widths of the characters on the left and right of the
+/// join, and `right_to_left` is set when the text is laid out right to left.
+/// Returns `None` if the two sub-characters cannot be smushed.
+pub fn smush(
+    lch: char,
+    rch: char,
+    mode: LayoutMode,
+    hardblank: char,
+    widths: (usize, usize),
+    right_to_left: bool,
+) -> Option<char> {
+    if lch == ' ' {
+        return Some(rch);
+    }
+    if rch == ' ' {
+        return Some(lch);
+    }
+
+    // smushing narrow characters looks awful
+    if widths.0 < 2 || widths.1 < 2 {
+        return None;
+    }
+
+    // kerning only joins at blanks
+    if mode & SM_SMUSH == 0 {
+        return None;
+    }
+
+    // universal smushing, the later character wins. that's the one on the
+    // right, unless the text runs right to left
+    if mode & SM_RULES == 0 {
+        if lch == hardblank {
+            return Some(rch);
+        }
+        if rch == hardblank || right_to_left {
+            return Some(lch);
+        }
+        return Some(rch);
+    }
+
+    if mode & SM_HARDBLANK != 0 && lch == hardblank && rch == hardblank {
+        return Some(lch);
+    }
+
+    if lch == hardblank || rch == hardblank {
+        return None;
+    }
+
+    if mode & SM_EQUAL != 0 && lch == rch {
+        return Some(lch);
+    }
+
+    if mode & SM_LOWLINE != 0 {
+        if let Some(res) = smush_heirarchy(lch, rch, TOP_CMP, TOP_HEIRARCHY) {
+            return Some(res);
+        }
+    }
+
+    if mode & SM_HEIRARCHY != 0 {
+        let rules = [
+            (BARLESS_CMP, BARLESS_HEIRARCHY),
+            (SLASHLESS_CMP, SLASHLESS_HEIRARCHY),
+            (BRACKETLESS_CMP, BRACKETLESS_HEIRARCHY),
+            (CURLYLESS_CMP, CURLYLESS_HEIRARCHY),
+            (PARENLESS_CMP, PARENLESS_HEIRARCHY),
+        ];
+        for (cmp, heirarchy) in rules {
+            if let Some(res) = smush_heirarchy(lch, rch, cmp, heirarchy) {
+                return Some(res);
+            }
+        }
+    }
+
+    if mode & SM_PAIR != 0 {
+        match (lch, rch) {
+            ('[', ']') | (']', '[') | ('{', '}') | ('}', '{') | ('(', ')') | (')', '(') => {
+                return Some('|');
+            }
+            _ => {}
+        }
+    }
+
+    if mode & SM_BIGX != 0 {
+        match (lch, rch) {
+            ('/', '\\') => return Some('|'),
+            ('\\', '/') => return Some('Y'),
+            ('>', '<') => return Some('X'),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// whichever character belongs to `heirarchy` replaces the one in `cmp`
+fn smush_heirarchy(lch: char, rch: char, cmp: &str, heirarchy: &str) -> Option<char> {
+    if cmp.contains(lch) && heirarchy.contains(rch) {
+        Some(rch)
+    } else if cmp.contains(rch) && heirarchy.contains(lch) {
+        Some(lch)
+    } else {
+        None
+    }
+}
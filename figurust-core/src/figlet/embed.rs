@@ -0,0 +1,116 @@
+use std::{collections::HashMap, fmt::Write};
+
+use super::{FIGcharacter, FIGfont, RawHeader};
+
+/// A font compiled into a program as static data, usually generated by
+/// `figurust-bin codegen` or `FIGfont::to_rust`
+///
+/// Turning it into a `FIGfont` only copies the rows, nothing is parsed.
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddedFont {
+    pub hardblank: char,
+    pub height: u16,
+    pub baseline: u16,
+    pub max_length: u16,
+    pub old_layout: i8,
+    pub direction: Option<u16>,
+    pub full_layout: Option<u16>,
+    pub codetag_count: Option<u16>,
+    pub comments: &'static str,
+    /// every glyph's rows without endmarks, sorted by code
+    pub characters: &'static [(u32, &'static [&'static str])],
+}
+
+impl EmbeddedFont {
+    pub fn header(&self) -> RawHeader {
+        RawHeader {
+            signature: format!("{}a", crate::FIGLET_FONT_SIGNATURE),
+            hardblank: self.hardblank,
+            height: self.height,
+            baseline: self.baseline,
+            max_length: self.max_length,
+            old_layout: self.old_layout,
+            comment_lines: match self.comments.is_empty() {
+                true => 0,
+                false => self.comments.split('\n').count() as u16,
+            },
+            direction: self.direction,
+            full_layout: self.full_layout,
+            codetag_count: self.codetag_count,
+        }
+    }
+
+    /// The rows of the glyph for `code`
+    pub fn glyph(&self, code: u32) -> Option<&'static [&'static str]> {
+        let idx = self
+            .characters
+            .binary_search_by_key(&code, |&(code, _)| code)
+            .ok()?;
+        Some(self.characters[idx].1)
+    }
+
+    pub fn to_font(&self) -> FIGfont {
+        let characters: HashMap<u32, FIGcharacter> = self
+            .characters
+            .iter()
+            .map(|&(code, rows)| {
+                let char_lines = rows.iter().map(|row| row.chars().collect()).collect();
+                (code, FIGcharacter::new(char_lines))
+            })
+            .collect();
+        FIGfont {
+            header: self.header(),
+            comments: self.comments.to_string(),
+            characters,
+        }
+    }
+}
+
+impl From<&EmbeddedFont> for FIGfont {
+    fn from(font: &EmbeddedFont) -> Self {
+        font.to_font()
+    }
+}
+
+impl FIGfont {
+    /// Writes Rust source declaring the font as a `pub static EmbeddedFont`
+    /// called `name`, for a crate to `include!`. A build script can write it
+    /// to `OUT_DIR` and include it with
+    /// `include!(concat!(env!("OUT_DIR"), "/font.rs"))`.
+    pub fn to_rust(&self, name: &str) -> String {
+        let header = &self.header;
+        let mut codes: Vec<u32> = self.characters.keys().copied().collect();
+        codes.sort_unstable();
+
+        // SAFETY: writing to a string can't fail
+        let mut source = String::new();
+        writeln!(source, "// generated by figurust, do not edit").unwrap();
+        writeln!(
+            source,
+            "pub static {name}: ::figurust::figlet::EmbeddedFont = ::figurust::figlet::EmbeddedFont {{"
+        )
+        .unwrap();
+        writeln!(source, "    hardblank: {:?},", header.hardblank).unwrap();
+        writeln!(source, "    height: {},", header.height).unwrap();
+        writeln!(source, "    baseline: {},", header.baseline).unwrap();
+        writeln!(source, "    max_length: {},", header.max_length).unwrap();
+        writeln!(source, "    old_layout: {},", header.old_layout).unwrap();
+        writeln!(source, "    direction: {:?},", header.direction).unwrap();
+        writeln!(source, "    full_layout: {:?},", header.full_layout).unwrap();
+        writeln!(source, "    codetag_count: {:?},", header.codetag_count).unwrap();
+        writeln!(source, "    comments: {:?},", self.comments).unwrap();
+        writeln!(source, "    characters: &[").unwrap();
+        for code in codes {
+            let rows = self.characters[&code]
+                .char_lines
+                .iter()
+                .map(|row| format!("{:?}", row.iter().collect::<String>()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(source, "        ({code}, &[{rows}]),").unwrap();
+        }
+        writeln!(source, "    ],").unwrap();
+        writeln!(source, "}};").unwrap();
+        source
+    }
+}
@@ -0,0 +1,240 @@
+use std::{collections::HashMap, path::Path, str::FromStr};
+
+use log::warn;
+use thiserror::Error;
+
+use crate::{figure::FIGure, settings::Settings};
+
+use super::*;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FIGfont {
+    // temp until i write a Header struct that uses the enums
+    pub header: RawHeader,
+    // pub header: Header,
+    pub comments: String,
+    pub characters: HashMap<u32, FIGcharacter>,
+}
+
+#[derive(Debug, Error)]
+pub enum FontLoadError {
+    #[error("Attempted to load a font from an empty string")]
+    EmptyString,
+    #[error("Missing header")]
+    MissingHeader,
+    #[error("Failed to parse header: {0}")]
+    HeaderError(#[from] HeaderParseError),
+    #[error("Failed to parse character: {0}")]
+    CharacterError(#[from] CharacterParseError),
+    #[error("Invalid code tag: {0:?}")]
+    InvalidCodeTag(String),
+    #[error("Font ends before the characters it has to have")]
+    Truncated,
+    #[error("IO Error: {0:?}")]
+    IoError(#[from] std::io::Error),
+    #[error("Not a compiled font")]
+    NotCompiled,
+    #[error("Failed to read compiled font: {0}")]
+    CompiledError(#[from] bincode::Error),
+}
+
+/// the characters every font must have after the ascii ones, in order
+pub(crate) const DEUTSCH_CHARACTERS: [u32; 7] = [196, 214, 220, 228, 246, 252, 223];
+
+impl FromStr for FIGfont {
+    type Err = FontLoadError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(FontLoadError::EmptyString);
+        }
+
+        let mut lines: Vec<_> = s.lines().collect();
+
+        let header: RawHeader = lines.first().ok_or(FontLoadError::MissingHeader)?.parse()?;
+        let comments = lines
+            .get(1..header.comment_lines as usize + 1)
+            .ok_or(FontLoadError::Truncated)?
+            .join("\n");
+        let mut characters = HashMap::new();
+
+        // these functions take the characters out of the lines vector
+        Self::read_required_characters(&mut lines, &header, &mut characters)?;
+        Self::read_tagged_characters(&mut lines, &header, &mut characters)?;
+
+        Ok(Self {
+            header,
+            comments,
+            characters,
+        })
+    }
+}
+
+// static methods
+impl FIGfont {
+    fn read_required_characters(
+        lines: &mut [&str],
+        header: &RawHeader,
+        map: &mut HashMap<u32, FIGcharacter>,
+    ) -> Result<(), FontLoadError> {
+        let offset = (header.comment_lines + 1) as usize;
+        let char_height = header.height as usize;
+
+        // ascii characters 32-126
+        for i in 32..=126 {
+            let idx = offset + ((i - 32) * char_height);
+
+            let rows = lines
+                .get_mut(idx..idx + char_height)
+                .ok_or(FontLoadError::Truncated)?;
+            let character = FIGcharacter::from_lines(rows)?;
+            map.insert(i as u32, character);
+        }
+
+        Ok(())
+    }
+
+    fn read_tagged_characters(
+        lines: &mut [&str],
+        header: &RawHeader,
+        map: &mut HashMap<u32, FIGcharacter>,
+    ) -> Result<(), FontLoadError> {
+        let char_height = header.height as usize;
+        let mut idx = (header.comment_lines + 1) as usize + 95 * char_height;
+
+        // older fonts stop after the ascii characters
+        for code in DEUTSCH_CHARACTERS {
+            if idx + char_height > lines.len() {
+                return Ok(());
+            }
+            let character = FIGcharacter::from_lines(&mut lines[idx..idx + char_height])?;
+            map.insert(code, character);
+            idx += char_height;
+        }
+
+        while idx + char_height < lines.len() {
+            let tag = lines[idx];
+            idx += 1;
+            if tag.trim().is_empty() {
+                continue;
+            }
+
+            // like figlet, a bad tag ends the font but keeps what came before
+            let code = match Self::parse_code_tag(tag) {
+                Ok(code) => code,
+                Err(e) => {
+                    warn!("{e}, ignoring the rest of the font");
+                    break;
+                }
+            };
+
+            let character = FIGcharacter::from_lines(&mut lines[idx..idx + char_height])?;
+            idx += char_height;
+
+            // negative codes are valid in figlet but can't be typed, so skip them
+            if let Some(code) = code {
+                map.insert(code, character);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the code from a code tag line, `None` if it's negative
+    pub(crate) fn parse_code_tag(tag: &str) -> Result<Option<u32>, FontLoadError> {
+        Ok(u32::try_from(Self::parse_signed_code_tag(tag)?).ok())
+    }
+
+    /// Reads the code from a code tag line, which can be decimal, octal or
+    /// hex, and negative
+    pub(crate) fn parse_signed_code_tag(tag: &str) -> Result<i64, FontLoadError> {
+        let invalid = || FontLoadError::InvalidCodeTag(tag.to_string());
+
+        let code = tag.split_whitespace().next().ok_or_else(invalid)?;
+        let (negative, code) = match code.strip_prefix('-') {
+            Some(code) => (true, code),
+            None => (false, code),
+        };
+
+        let code = if let Some(hex) = code.strip_prefix("0x").or(code.strip_prefix("0X")) {
+            u32::from_str_radix(hex, 16)
+        } else if code.len() > 1 && code.starts_with('0') {
+            u32::from_str_radix(&code[1..], 8)
+        } else {
+            code.parse()
+        }
+        .map_err(|_| invalid())?;
+
+        let code = i64::from(code);
+        Ok(if negative { -code } else { code })
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, FontLoadError> {
+        read_source(path)?.parse()
+    }
+}
+
+/// Reads a font file as text
+pub(crate) fn read_source(path: impl AsRef<Path>) -> std::io::Result<String> {
+    let contents = std::fs::read(path)?;
+    // plenty of older fonts are Latin-1, whose bytes map straight to chars
+    Ok(String::from_utf8(contents)
+        .unwrap_or_else(|err| err.into_bytes().iter().map(|&b| b as char).collect()))
+}
+
+#[derive(Debug, Error)]
+pub enum FontConvertError {
+    #[error("Font does not contain character: '{0}'")]
+    MissingCharacter(char),
+}
+
+/// The size of rendered text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Measurement {
+    pub width: usize,
+    pub height: usize,
+}
+
+// instance methods
+impl FIGfont {
+    pub fn convert(
+        &self,
+        content: impl AsRef<str>,
+        settings: &Settings,
+    ) -> Result<String, FontConvertError> {
+        FontChain::new(self).convert(content, settings)
+    }
+
+    /// Lays out `content` into a grid of cells, one font height per input line
+    pub fn render(
+        &self,
+        content: impl AsRef<str>,
+        settings: &Settings,
+    ) -> Result<FIGure, FontConvertError> {
+        FontChain::new(self).render(content, settings)
+    }
+
+    /// Works out the size `render` would produce without building the output
+    pub fn measure(
+        &self,
+        content: impl AsRef<str>,
+        settings: &Settings,
+    ) -> Result<Measurement, FontConvertError> {
+        FontChain::new(self).measure(content, settings)
+    }
+
+    /// Picks the tallest font that can render `content` within `width` columns
+    pub fn largest_fitting<'a>(
+        fonts: impl IntoIterator<Item = &'a FIGfont>,
+        content: impl AsRef<str>,
+        settings: &Settings,
+        width: usize,
+    ) -> Option<&'a FIGfont> {
+        let content = content.as_ref();
+        fonts
+            .into_iter()
+            .filter_map(|font| Some((font, font.measure(content, settings).ok()?)))
+            .filter(|(_, size)| size.width <= width)
+            .max_by_key(|(_, size)| (size.height, size.width))
+            .map(|(font, _)| font)
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt::Write;
+
+use super::{font::DEUTSCH_CHARACTERS, FIGcharacter, FIGfont, FontLoadError};
+
+/// Starts every compiled font, followed by the format's version
+const COMPILED_MAGIC: &[u8; 4] = b"FIGc";
+const COMPILED_VERSION: u8 = 1;
+
+// endmarks to pick from, so a glyph whose rows end in '@' still reads back
+const ENDMARKS: [char; 5] = ['@', '#', '%', '&', '|'];
+
+impl FIGfont {
+    /// Writes the font in figlet's `.flf` format
+    pub fn to_flf(&self) -> String {
+        self.write_source(crate::FIGLET_FONT_SIGNATURE)
+    }
+
+    /// Writes the font in toilet's `.tlf` format, which is `.flf` with a
+    /// different signature
+    pub fn to_tlf(&self) -> String {
+        self.write_source(crate::TOILET_FONT_SIGNATURE)
+    }
+
+    /// Packs the font into the compiled format, which loads without any
+    /// text parsing
+    pub fn to_compiled(&self) -> Vec<u8> {
+        let mut bytes = COMPILED_MAGIC.to_vec();
+        bytes.push(COMPILED_VERSION);
+        // SAFETY: every part of a font can be serialized
+        bincode::serialize_into(&mut bytes, self).unwrap();
+        bytes
+    }
+
+    /// Loads a font from the compiled format
+    pub fn from_compiled(bytes: &[u8]) -> Result<Self, FontLoadError> {
+        match bytes.strip_prefix(COMPILED_MAGIC) {
+            Some([COMPILED_VERSION, font @ ..]) => Ok(bincode::deserialize(font)?),
+            _ => Err(FontLoadError::NotCompiled),
+        }
+    }
+
+    fn write_source(&self, signature: &str) -> String {
+        let mut tagged: Vec<u32> = self
+            .characters
+            .keys()
+            .copied()
+            .filter(|code| !(32..=126).contains(code) && !DEUTSCH_CHARACTERS.contains(code))
+            .collect();
+        tagged.sort_unstable();
+
+        // the deutsch characters have to be there for anything after them
+        // to be read, missing ones are written empty
+        let deutsch = !tagged.is_empty()
+            || DEUTSCH_CHARACTERS
+                .iter()
+                .any(|code| self.characters.contains_key(code));
+
+        let mut header = self.header.clone();
+        header.signature = format!("{signature}a");
+        let comments: Vec<&str> = match self.comments.is_empty() {
+            true => vec![],
+            false => self.comments.split('\n').collect(),
+        };
+        header.comment_lines = comments.len() as u16;
+        header.max_length = self
+            .characters
+            .values()
+            .flat_map(|character| &character.char_lines)
+            .map(|row| row.len() + 2)
+            .max()
+            .unwrap_or(2) as u16;
+        if header.codetag_count.is_some() || !tagged.is_empty() {
+            header.codetag_count = Some(tagged.len() as u16);
+        }
+
+        let mut source = format!("{header}\n");
+        for line in comments {
+            source.push_str(line);
+            source.push('\n');
+        }
+        for code in 32..=126 {
+            self.write_character(&mut source, code);
+        }
+        if deutsch {
+            for code in DEUTSCH_CHARACTERS {
+                self.write_character(&mut source, code);
+            }
+        }
+        for code in tagged {
+            // SAFETY: writing to a string can't fail
+            writeln!(source, "{code}  U+{code:04X}").unwrap();
+            self.write_character(&mut source, code);
+        }
+        source
+    }
+
+    fn write_character(&self, source: &mut String, code: u32) {
+        let empty = FIGcharacter::new(vec![]);
+        let character = self.characters.get(&code).unwrap_or(&empty);
+        let endmark = ENDMARKS
+            .into_iter()
+            .find(|endmark| {
+                let ends = |row: &Vec<char>| row.last() == Some(endmark);
+                !character.char_lines.iter().any(ends)
+            })
+            .unwrap_or('@');
+
+        let height = self.header.height as usize;
+        for row_idx in 0..height {
+            if let Some(row) = character.char_lines.get(row_idx) {
+                source.extend(row);
+            }
+            source.push(endmark);
+            if row_idx == height - 1 {
+                source.push(endmark);
+            }
+            source.push('\n');
+        }
+    }
+}
@@ -1,14 +1,15 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use thiserror::Error;
 
-use crate::FIGLET_FONT_SIGNATURE;
+use crate::{FIGLET_FONT_SIGNATURE, TOILET_FONT_SIGNATURE};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RawHeader {
     #[serde(skip)]
     pub signature: String,
-    #[serde(skip)]
+    // fonts saved before the hardblank was stored almost all used '$'
+    #[serde(default = "default_hardblank")]
     pub hardblank: char,
 
     pub height: u16,
@@ -73,18 +74,50 @@ impl FromStr for RawHeader {
     }
 }
 
+fn default_hardblank() -> char {
+    '$'
+}
+
+/// Writes the header line, leaving off optional fields that aren't set
+impl fmt::Display for RawHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{} {} {} {} {} {}",
+            self.signature,
+            self.hardblank,
+            self.height,
+            self.baseline,
+            self.max_length,
+            self.old_layout,
+            self.comment_lines
+        )?;
+        // each optional field needs the ones before it
+        let optional = [self.direction, self.full_layout, self.codetag_count];
+        let count = optional
+            .iter()
+            .rposition(Option::is_some)
+            .map_or(0, |idx| idx + 1);
+        for field in &optional[..count] {
+            write!(f, " {}", field.unwrap_or(0))?;
+        }
+        Ok(())
+    }
+}
+
 impl RawHeader {
     fn read_signature(segment: &str) -> Result<(String, char), HeaderParseError> {
-        let signature: String = segment.chars().take(segment.len() - 1).collect();
-        if !signature.starts_with(FIGLET_FONT_SIGNATURE) {
+        let mut chars = segment.chars();
+        let hardblank = chars
+            .next_back()
+            .ok_or(HeaderParseError::MissingHardblank)?;
+        let signature = chars.as_str().to_string();
+        if !signature.starts_with(FIGLET_FONT_SIGNATURE)
+            && !signature.starts_with(TOILET_FONT_SIGNATURE)
+        {
             return Err(HeaderParseError::InvalidSignature(signature));
         }
 
-        let hardblank = segment
-            .chars()
-            .nth(segment.len() - 1)
-            .ok_or(HeaderParseError::MissingHardblank)?;
-
         Ok((signature, hardblank))
     }
 
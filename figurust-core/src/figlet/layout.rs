@@ -0,0 +1,412 @@
+use std::collections::HashMap;
+
+use crate::{
+    figure::Cell,
+    settings::{Direction, Justify, Settings, Spacing},
+};
+
+use super::{smush, FIGcharacter, FIGfont, RawHeader, RowProfile};
+
+// these mirror figlet's `smushmode` flags, which are also the low byte of
+// the `full_layout` header field
+
+pub type LayoutMode = u16;
+
+// not a flag, but a value
+pub const SM_FULLWIDTH: LayoutMode = 0;
+
+// flags
+pub const SM_EQUAL: LayoutMode = 1 << 0;
+pub const SM_LOWLINE: LayoutMode = 1 << 1;
+pub const SM_HEIRARCHY: LayoutMode = 1 << 2;
+pub const SM_PAIR: LayoutMode = 1 << 3;
+pub const SM_BIGX: LayoutMode = 1 << 4;
+pub const SM_HARDBLANK: LayoutMode = 1 << 5;
+pub const SM_KERN: LayoutMode = 1 << 6;
+pub const SM_SMUSH: LayoutMode = 1 << 7;
+
+/// all of the horizontal smushing rules
+pub const SM_RULES: LayoutMode =
+    SM_EQUAL | SM_LOWLINE | SM_HEIRARCHY | SM_PAIR | SM_BIGX | SM_HARDBLANK;
+
+impl RawHeader {
+    /// The horizontal layout the font asks for by default.
+    ///
+    /// `full_layout` takes precedence, otherwise this is derived from
+    /// `old_layout` the same way figlet does it.
+    pub fn layout(&self) -> LayoutMode {
+        if let Some(full_layout) = self.full_layout {
+            return full_layout & 0xff;
+        }
+
+        match self.old_layout {
+            0 => SM_KERN,
+            layout if layout < 0 => SM_FULLWIDTH,
+            layout => (layout as LayoutMode & SM_RULES) | SM_SMUSH,
+        }
+    }
+}
+
+impl Settings {
+    /// Combines the font's default layout with the overrides in these settings.
+    pub fn layout_mode(&self, header: &RawHeader) -> LayoutMode {
+        if let Some(mode) = self.layout_mode {
+            return match mode {
+                0 => SM_KERN,
+                mode => (mode as LayoutMode & SM_RULES) | SM_SMUSH,
+            };
+        }
+
+        if self.overlap {
+            return SM_SMUSH;
+        }
+
+        match self.spacing {
+            Spacing::Smushing => header.layout(),
+            Spacing::ForceSmushing => header.layout() | SM_SMUSH,
+            Spacing::Kerning => SM_KERN,
+            Spacing::FullWidth => SM_FULLWIDTH,
+        }
+    }
+
+    /// Whether text is laid out right to left, the font decides if the
+    /// direction is `Auto`
+    pub fn right_to_left(&self, header: &RawHeader) -> bool {
+        match self.direction {
+            Direction::LeftToRight => false,
+            Direction::RightToLeft => true,
+            Direction::Auto => header.direction == Some(1),
+        }
+    }
+
+    /// The widest a line of output can be, `None` if lines are never
+    /// wrapped. Like figlet, this leaves the last column free.
+    pub fn line_limit(&self) -> Option<usize> {
+        self.width.filter(|&width| width > 0).map(|width| width - 1)
+    }
+
+    /// How many columns to shift a line of `width` right by
+    pub fn justify_padding(&self, width: usize, right_to_left: bool) -> usize {
+        let Some(limit) = self.line_limit() else {
+            return 0;
+        };
+        let space = limit.saturating_sub(width);
+        match self.justify {
+            Justify::Left => 0,
+            // figlet centers against the full width, so odd space rounds up
+            Justify::Center => space.div_ceil(2),
+            Justify::Right => space,
+            Justify::Auto if right_to_left => space,
+            Justify::Auto => 0,
+        }
+    }
+}
+
+/// What the layout needs to know about a row of output: its length and
+/// its rightmost non-blank sub-character
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct RowEdge {
+    pub len: usize,
+    pub last: Option<(usize, char)>,
+}
+
+impl RowEdge {
+    /// Moves the edge past a row of a character once it has been joined
+    /// `amount` columns into the output, `smush` is used where the new
+    /// rightmost sub-character lands on the old one
+    pub fn advance(
+        &mut self,
+        row: &RowProfile,
+        row_len: usize,
+        amount: usize,
+        smush: impl Fn(char, char) -> Option<char>,
+    ) {
+        let len = self.len;
+        self.len = self.len.max((len + row_len).saturating_sub(amount));
+
+        // the layout never moves a character far enough for any of its
+        // sub-characters to land left of the old edge, so only the
+        // rightmost one matters
+        let Some((col, rch)) = row.last else {
+            return;
+        };
+        if len + col < amount {
+            return;
+        }
+        let idx = len + col - amount;
+
+        match self.last {
+            Some((last, lch)) if idx == last => {
+                if let Some(ch) = smush(lch, rch) {
+                    self.last = Some((idx, ch));
+                }
+            }
+            Some((last, _)) if idx < last => {}
+            _ => self.last = Some((idx, rch)),
+        }
+    }
+}
+
+// stands in for every font's hardblank while laying out, so fonts with
+// different hardblanks can be mixed on one line. it's a noncharacter, so
+// no font should ever draw it
+const HARDBLANK: char = '\u{ffff}';
+
+/// A character along with the font it came from
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Glyph<'a> {
+    pub font: &'a FIGfont,
+    pub character: &'a FIGcharacter,
+}
+
+/// How far a line reaches above and below its baseline
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Extent {
+    pub above: usize,
+    pub below: usize,
+}
+
+impl Extent {
+    pub fn of(font: &FIGfont) -> Self {
+        let height = font.header.height as usize;
+        // plenty of fonts have a baseline taller than they are
+        let above = (font.header.baseline as usize).min(height);
+        Self {
+            above,
+            below: height - above,
+        }
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            above: self.above.max(other.above),
+            below: self.below.max(other.below),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.above + self.below
+    }
+}
+
+/// How far glyphs were moved into the glyph before them, kept for every line
+/// of a layout since text and wrapping keep laying out the same pairs
+///
+/// An amount only depends on the two glyphs when the first one ends every
+/// row of the line, so it's only looked up then.
+#[derive(Debug, Default)]
+pub(crate) struct PairCache {
+    amounts: HashMap<(*const FIGcharacter, *const FIGcharacter, LayoutMode), usize>,
+}
+
+impl PairCache {
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.amounts.len()
+    }
+}
+
+/// One line of output, laid out a glyph at a time
+///
+/// Glyphs from shorter fonts sit on the line's baseline, with blank rows
+/// above and below them.
+#[derive(Debug)]
+pub(crate) struct LineLayout {
+    extent: Extent,
+    edges: Vec<RowEdge>,
+    /// `None` when the line is only being measured
+    rows: Option<Vec<Vec<Cell>>>,
+    prev_width: usize,
+    /// the last glyph pushed, if it ends every row of the line
+    tail: Option<*const FIGcharacter>,
+    /// the glyphs are pushed in reverse, so the later ones are on the left
+    right_to_left: bool,
+}
+
+impl LineLayout {
+    pub fn new(extent: Extent, draw: bool, right_to_left: bool) -> Self {
+        let height = extent.height();
+        Self {
+            extent,
+            edges: vec![RowEdge::default(); height],
+            rows: draw.then(|| vec![vec![]; height]),
+            prev_width: 0,
+            tail: None,
+            right_to_left,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.edges.iter().map(|edge| edge.len).max().unwrap_or(0)
+    }
+
+    pub fn height(&self) -> usize {
+        self.extent.height()
+    }
+
+    /// The drawn rows shifted right by `padding`, with hardblanks turned
+    /// back into spaces
+    pub fn into_rows(self, padding: usize) -> Vec<Vec<Cell>> {
+        let mut rows = self.rows.unwrap_or_default();
+        for row in &mut rows {
+            for cell in row.iter_mut() {
+                if cell.ch == HARDBLANK {
+                    cell.ch = ' ';
+                }
+            }
+            row.splice(0..0, std::iter::repeat_n(Cell::BLANK, padding));
+        }
+        rows
+    }
+
+    /// Adds `glyph` to the end of the line, drawn by the input character at
+    /// byte offset `source`
+    pub fn push(
+        &mut self,
+        glyph: Glyph<'_>,
+        source: usize,
+        settings: &Settings,
+        cache: &mut PairCache,
+    ) {
+        let Glyph { font, character } = glyph;
+        let mode = settings.layout_mode(&font.header);
+        let hardblank = font.header.hardblank;
+        let widths = (self.prev_width, character.width);
+        let right_to_left = self.right_to_left;
+        let smush = |lch, rch| smush(lch, rch, mode, HARDBLANK, widths, right_to_left);
+
+        let top = self.extent.above - Extent::of(font).above;
+        let rows: Vec<(RowProfile, Option<&[char]>)> = (0..self.extent.height())
+            .map(|y| {
+                let r = y.checked_sub(top)?;
+                let profile = character.profile.get(r)?;
+                let line = character.char_lines.get(r)?;
+                let profile = RowProfile {
+                    left: profile.left,
+                    first: profile.first.map(|ch| unhard(ch, hardblank)),
+                    last: profile.last.map(|(idx, ch)| (idx, unhard(ch, hardblank))),
+                };
+                Some((profile, Some(line.as_slice())))
+            })
+            .map(|row| {
+                // rows the glyph doesn't reach are as blank as it is wide
+                row.unwrap_or((
+                    RowProfile {
+                        left: character.width,
+                        first: None,
+                        last: None,
+                    },
+                    None,
+                ))
+            })
+            .collect();
+
+        let amount = match self.tail {
+            Some(prev) => *cache
+                .amounts
+                .entry((prev, character, mode))
+                .or_insert_with(|| self.smush_amount(&rows, character.width, mode, smush)),
+            None => self.smush_amount(&rows, character.width, mode, smush),
+        };
+
+        if let Some(output) = &mut self.rows {
+            for (row, (_, line)) in output.iter_mut().zip(&rows) {
+                let chars = match line {
+                    Some(line) => line.iter().map(|&ch| unhard(ch, hardblank)).collect(),
+                    None => vec![' '; character.width],
+                };
+                add_row(row, chars, source, amount, smush);
+            }
+        }
+
+        // whether the glyph's own rightmost sub-characters end every row
+        let mut tail = true;
+        for (edge, (profile, line)) in self.edges.iter_mut().zip(&rows) {
+            let len = line.map_or(character.width, <[char]>::len);
+            let start = edge.len;
+            edge.advance(profile, len, amount, smush);
+            tail &= profile.last.is_some_and(|(col, ch)| {
+                start + col >= amount
+                    && edge.len == start + len - amount
+                    && edge.last == Some((start + col - amount, ch))
+            });
+        }
+
+        self.prev_width = character.width;
+        self.tail = tail.then_some(character as *const FIGcharacter);
+    }
+
+    /// How many columns a glyph can be moved left into the line
+    fn smush_amount(
+        &self,
+        rows: &[(RowProfile, Option<&[char]>)],
+        width: usize,
+        mode: LayoutMode,
+        smush: impl Fn(char, char) -> Option<char>,
+    ) -> usize {
+        if mode & (SM_SMUSH | SM_KERN) == 0 {
+            return 0;
+        }
+
+        let mut max_smush = width;
+        for (edge, (row, _)) in self.edges.iter().zip(rows) {
+            // rightmost non-blank sub-character of the output so far
+            let amount = match edge.last {
+                Some((line_bound, lch)) => {
+                    let gap = row.left + edge.len - line_bound - 1;
+                    match row.first {
+                        Some(rch) if smush(lch, rch).is_some() => gap + 1,
+                        _ => gap,
+                    }
+                }
+                None => row.left + edge.len,
+            };
+
+            max_smush = max_smush.min(amount);
+        }
+
+        max_smush
+    }
+}
+
+fn unhard(ch: char, hardblank: char) -> char {
+    if ch == hardblank {
+        HARDBLANK
+    } else {
+        ch
+    }
+}
+
+/// Draws one row of a glyph `amount` columns into the end of `row`
+fn add_row(
+    row: &mut Vec<Cell>,
+    chars: Vec<char>,
+    source: usize,
+    amount: usize,
+    smush: impl Fn(char, char) -> Option<char>,
+) {
+    let mut chars = chars.into_iter();
+    let len = row.len();
+    for k in 0..amount {
+        let Some(rch) = chars.next() else {
+            break;
+        };
+        // the first character can overlap the start of the line
+        if len + k < amount {
+            continue;
+        }
+        let cell = &mut row[len + k - amount];
+        if cell.is_blank() {
+            *cell = Cell::new(rch, source);
+        } else if rch != ' ' {
+            if let Some(ch) = smush(cell.ch, rch) {
+                if ch != cell.ch {
+                    cell.source = Some(source);
+                }
+                cell.ch = ch;
+                cell.smushed = true;
+            }
+        }
+    }
+    row.extend(chars.map(|ch| Cell::new(ch, source)));
+}
@@ -0,0 +1,20 @@
+mod chain;
+mod chars;
+mod embed;
+mod font;
+mod format;
+mod header;
+pub mod layout;
+mod registry;
+mod search;
+mod validate;
+
+pub(crate) use chain::lay_out;
+pub use chain::FontChain;
+pub use chars::{smush, CharacterParseError, FIGcharacter, RowProfile};
+pub use embed::EmbeddedFont;
+pub use font::{FIGfont, FontConvertError, FontLoadError, Measurement};
+pub use header::{HeaderParseError, RawHeader};
+pub use registry::{FontRegistry, RegistryError};
+pub use search::{SearchPath, BUNDLED_FONTS};
+pub use validate::{validate, validate_file, Diagnostic, Problem, Severity};
@@ -0,0 +1,229 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+    time::SystemTime,
+};
+
+use log::{debug, trace};
+use thiserror::Error;
+
+use super::{FIGfont, FontLoadError, SearchPath, BUNDLED_FONTS};
+
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error("No font named {name:?}, tried:{}", list_paths(.tried))]
+    NotFound { name: String, tried: Vec<PathBuf> },
+    #[error("Failed to load font {path:?}: {source}")]
+    LoadError {
+        path: PathBuf,
+        #[source]
+        source: FontLoadError,
+    },
+}
+
+/// A font the registry knows about, which may not have been loaded yet
+#[derive(Debug)]
+struct Entry {
+    name: String,
+    source: Source,
+    // the lock is held while parsing so each font is only read once
+    font: Mutex<Option<Arc<FIGfont>>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Source {
+    File {
+        path: PathBuf,
+        modified: Option<SystemTime>,
+    },
+    Bundled(&'static str),
+}
+
+/// An index of the fonts in one or more directories
+///
+/// Fonts are looked up by name, ignoring case and an optional `.flf` or
+/// `.tlf` extension. They are only read the first time they're asked for, and
+/// are shared between every caller after that, so one registry can be
+/// used from any number of threads.
+#[derive(Debug)]
+pub struct FontRegistry {
+    search_path: SearchPath,
+    entries: RwLock<HashMap<String, Arc<Entry>>>,
+}
+
+impl FontRegistry {
+    /// Indexes the fonts in `directories`. If a name shows up in more than
+    /// one directory, the one listed first wins. Directories that don't
+    /// exist are skipped.
+    pub fn new<P: Into<PathBuf>>(directories: impl IntoIterator<Item = P>) -> Self {
+        Self::from_search_path(&SearchPath::new(directories))
+    }
+
+    /// Indexes the fonts on `search_path`, including the bundled ones if
+    /// it asks for them
+    pub fn from_search_path(search_path: &SearchPath) -> Self {
+        let registry = Self {
+            search_path: search_path.clone(),
+            entries: RwLock::default(),
+        };
+        registry.rescan();
+        registry
+    }
+
+    pub fn search_path(&self) -> &SearchPath {
+        &self.search_path
+    }
+
+    /// Reads the directories again to pick up fonts that were added,
+    /// removed or changed. Fonts whose files haven't changed stay loaded.
+    pub fn rescan(&self) {
+        let mut entries = self.entries.write().unwrap();
+        let mut scanned = HashMap::new();
+
+        for directory in &self.search_path.directories {
+            let files = match fs::read_dir(directory) {
+                Ok(files) => files,
+                Err(err) => {
+                    debug!("skipping font directory {directory:?}: {err}");
+                    continue;
+                }
+            };
+
+            // sorted so the index doesn't depend on the order of the filesystem
+            let mut paths: Vec<PathBuf> = files
+                .filter_map(|file| file.ok().map(|file| file.path()))
+                .filter(|path| path.is_file() && is_font(path))
+                .collect();
+            paths.sort();
+
+            for path in paths {
+                let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                    continue;
+                };
+                let name = name.to_string();
+                let key = font_key(&name);
+                if scanned.contains_key(&key) {
+                    continue;
+                }
+
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+                let source = Source::File { path, modified };
+                scanned.insert(key, reuse_entry(&entries, &name, source));
+            }
+        }
+
+        if self.search_path.bundled {
+            for &(name, contents) in BUNDLED_FONTS {
+                scanned
+                    .entry(font_key(name))
+                    .or_insert_with(|| reuse_entry(&entries, name, Source::Bundled(contents)));
+            }
+        }
+
+        *entries = scanned;
+    }
+
+    /// The names of every font in the registry, sorted
+    pub fn names(&self) -> Vec<String> {
+        let entries = self.entries.read().unwrap();
+        let mut names: Vec<String> = entries.values().map(|entry| entry.name.clone()).collect();
+        names.sort_by_key(|name| name.to_lowercase());
+        names
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.read().unwrap().contains_key(&font_key(name))
+    }
+
+    /// Where the font called `name` lives, `None` if it's bundled or
+    /// doesn't exist
+    pub fn path(&self, name: &str) -> Option<PathBuf> {
+        let entries = self.entries.read().unwrap();
+        match &entries.get(&font_key(name))?.source {
+            Source::File { path, .. } => Some(path.clone()),
+            Source::Bundled(_) => None,
+        }
+    }
+
+    /// Gets the font called `name`, loading it if this is the first time
+    /// it's been asked for
+    pub fn get(&self, name: &str) -> Result<Arc<FIGfont>, RegistryError> {
+        let entry = self
+            .entries
+            .read()
+            .unwrap()
+            .get(&font_key(name))
+            .cloned()
+            .ok_or_else(|| RegistryError::NotFound {
+                name: name.to_string(),
+                tried: self.search_path.candidates(name),
+            })?;
+
+        let mut font = entry.font.lock().unwrap();
+        if let Some(font) = &*font {
+            return Ok(font.clone());
+        }
+
+        let loaded = match &entry.source {
+            Source::File { path, .. } => {
+                trace!("loading font {path:?}");
+                FIGfont::from_file(path).map_err(|source| RegistryError::LoadError {
+                    path: path.clone(),
+                    source,
+                })?
+            }
+            Source::Bundled(contents) => {
+                contents
+                    .parse()
+                    .map_err(|source| RegistryError::LoadError {
+                        path: PathBuf::from(&entry.name),
+                        source,
+                    })?
+            }
+        };
+        let loaded = Arc::new(loaded);
+        *font = Some(loaded.clone());
+        Ok(loaded)
+    }
+}
+
+// toilet's fonts are figlet fonts with a different signature, so both load
+const EXTENSIONS: [&str; 2] = ["flf", "tlf"];
+
+fn is_font(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        EXTENSIONS
+            .iter()
+            .any(|extension| ext.eq_ignore_ascii_case(extension))
+    })
+}
+
+/// names are compared without case or the `.flf` or `.tlf` extension
+fn font_key(name: &str) -> String {
+    let name = name.to_lowercase();
+    EXTENSIONS
+        .iter()
+        .find_map(|extension| name.strip_suffix(&format!(".{extension}")))
+        .map_or_else(|| name.clone(), str::to_string)
+}
+
+/// keeps a loaded font around if its source hasn't changed
+fn reuse_entry(entries: &HashMap<String, Arc<Entry>>, name: &str, source: Source) -> Arc<Entry> {
+    match entries.get(&font_key(name)) {
+        Some(entry) if entry.source == source => entry.clone(),
+        _ => Arc::new(Entry {
+            name: name.to_string(),
+            source,
+            font: Mutex::default(),
+        }),
+    }
+}
+
+fn list_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| format!("\n  {}", path.display()))
+        .collect()
+}
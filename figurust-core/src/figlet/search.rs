@@ -0,0 +1,99 @@
+use std::{path::PathBuf, sync::Arc};
+
+use crate::settings::Settings;
+
+use super::{FIGfont, FontRegistry, RegistryError};
+
+/// Fonts compiled into the library, used when no directory has them
+pub const BUNDLED_FONTS: &[(&str, &str)] = &[("small", include_str!("../../fonts/small.flf"))];
+
+/// The places fonts are looked for, in order of precedence
+///
+/// When a name is in more than one directory, the first directory wins.
+/// The bundled fonts always come last.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchPath {
+    pub directories: Vec<PathBuf>,
+    pub bundled: bool,
+}
+
+impl SearchPath {
+    /// A search path of only these directories
+    pub fn new<P: Into<PathBuf>>(directories: impl IntoIterator<Item = P>) -> Self {
+        Self {
+            directories: directories.into_iter().map(Into::into).collect(),
+            bundled: false,
+        }
+    }
+
+    /// The user's fonts, then the project's, then the system's figlet
+    /// fonts, then the bundled ones
+    pub fn standard() -> Self {
+        let mut directories = vec![];
+        directories.extend(Self::user_directory());
+        directories.push(Self::project_directory());
+        directories.extend(Self::system_directories());
+        Self {
+            directories,
+            bundled: true,
+        }
+    }
+
+    /// `figurust` in the user's data directory
+    pub fn user_directory() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("figurust"))
+    }
+
+    /// `fonts` in the current directory
+    pub fn project_directory() -> PathBuf {
+        PathBuf::from("fonts")
+    }
+
+    /// Where figlet installs its fonts
+    pub fn system_directories() -> Vec<PathBuf> {
+        if cfg!(unix) {
+            vec![
+                PathBuf::from("/usr/local/share/figlet"),
+                PathBuf::from("/usr/share/figlet"),
+            ]
+        } else {
+            vec![]
+        }
+    }
+
+    /// Moves `directory` to the front, so it's searched before anything else
+    pub fn prepend(&mut self, directory: impl Into<PathBuf>) {
+        let directory = directory.into();
+        self.directories.retain(|dir| *dir != directory);
+        self.directories.insert(0, directory);
+    }
+
+    /// Every file a font called `name` could be loaded from, in the order
+    /// they're tried
+    pub fn candidates(&self, name: &str) -> Vec<PathBuf> {
+        let file = if name.to_lowercase().ends_with(".flf") {
+            name.to_string()
+        } else {
+            format!("{name}.flf")
+        };
+        self.directories.iter().map(|dir| dir.join(&file)).collect()
+    }
+
+    /// Indexes every font on the path
+    pub fn registry(&self) -> FontRegistry {
+        FontRegistry::from_search_path(self)
+    }
+}
+
+impl Default for SearchPath {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl Settings {
+    /// Finds and loads the font these settings ask for
+    pub fn load_font(&self) -> Result<Arc<FIGfont>, RegistryError> {
+        self.font_path.registry().get(&self.font)
+    }
+}
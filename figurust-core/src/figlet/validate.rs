@@ -0,0 +1,358 @@
+use std::{collections::HashMap, fmt, io, path::Path};
+
+use thiserror::Error;
+
+use super::{
+    font::{read_source, DEUTSCH_CHARACTERS},
+    FIGfont, RawHeader,
+};
+
+/// How much a problem matters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// the font loads, but probably doesn't look the way it was meant to
+    Warning,
+    /// the font won't load, or loads with glyphs in the wrong places
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Something wrong with a font file, like the ones figlet's `chkfont` finds
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Problem {
+    #[error("Invalid header: {0}")]
+    InvalidHeader(String),
+    #[error("comment_lines is {declared} but the comments are {found} lines long")]
+    CommentLinesMismatch { declared: usize, found: usize },
+    #[error("Row of character {code} is {found} columns wide, its first row is {expected}")]
+    InconsistentWidth {
+        code: u32,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Row of character {code} is {length} long, longer than max_length {max_length}")]
+    RowTooLong {
+        code: u32,
+        length: usize,
+        max_length: usize,
+    },
+    #[error("Character {code} is {found} rows tall, the font is {expected}")]
+    WrongHeight {
+        code: u32,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Row of character {code} has no endmark")]
+    MissingEndmark { code: u32 },
+    #[error("Row of character {code} ends with {found:?}, its first row ends with {expected:?}")]
+    InconsistentEndmark {
+        code: u32,
+        expected: char,
+        found: char,
+    },
+    #[error("Invalid code tag: {0:?}")]
+    InvalidCodeTag(String),
+    #[error("Character {code} was already defined on line {first_line}")]
+    DuplicateCodeTag { code: i64, first_line: usize },
+    #[error("codetag_count is {declared} but there are {found} code-tagged characters")]
+    CodetagCountMismatch { declared: usize, found: usize },
+}
+
+impl Problem {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Problem::InvalidHeader(_)
+            | Problem::CommentLinesMismatch { .. }
+            | Problem::WrongHeight { .. }
+            | Problem::MissingEndmark { .. }
+            | Problem::InvalidCodeTag(_) => Severity::Error,
+            Problem::InconsistentWidth { .. }
+            | Problem::RowTooLong { .. }
+            | Problem::InconsistentEndmark { .. }
+            | Problem::DuplicateCodeTag { .. }
+            | Problem::CodetagCountMismatch { .. } => Severity::Warning,
+        }
+    }
+}
+
+/// A problem along with the line of the font file it's on, counting from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub problem: Problem,
+}
+
+impl Diagnostic {
+    pub fn severity(&self) -> Severity {
+        self.problem.severity()
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.line, self.severity(), self.problem)
+    }
+}
+
+/// Checks the source of a font file for problems, in the order they appear
+pub fn validate(source: &str) -> Vec<Diagnostic> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut diagnostics = vec![];
+
+    let header = match lines.first().map(|line| line.parse::<RawHeader>()) {
+        Some(Ok(header)) => header,
+        Some(Err(e)) => {
+            diagnostics.push(Diagnostic {
+                line: 1,
+                problem: Problem::InvalidHeader(e.to_string()),
+            });
+            return diagnostics;
+        }
+        None => {
+            diagnostics.push(Diagnostic {
+                line: 1,
+                problem: Problem::InvalidHeader("the file is empty".to_string()),
+            });
+            return diagnostics;
+        }
+    };
+
+    let mut validator = Validator {
+        lines: &lines,
+        height: header.height as usize,
+        hardblank: header.hardblank,
+        max_length: header.max_length as usize,
+        diagnostics,
+    };
+
+    // if the comment count is wrong everything after it is misread, so the
+    // rest is checked from wherever the characters really start
+    let declared = header.comment_lines as usize;
+    let mut idx = 1 + declared;
+    if !validator.is_space(idx) {
+        if let Some(start) = (1..lines.len()).find(|&start| validator.is_space(start)) {
+            validator.push(
+                idx.min(start),
+                Problem::CommentLinesMismatch {
+                    declared,
+                    found: start - 1,
+                },
+            );
+            idx = start;
+        }
+    }
+
+    // a truncated font is only reported once, at the first short character
+    for code in 32..=126 {
+        let Some(next) = validator.check_character(idx, code) else {
+            return validator.diagnostics;
+        };
+        idx = next;
+    }
+    // older fonts stop after the ascii characters
+    for code in DEUTSCH_CHARACTERS {
+        if idx + validator.height > lines.len() {
+            break;
+        }
+        let Some(next) = validator.check_character(idx, code) else {
+            return validator.diagnostics;
+        };
+        idx = next;
+    }
+
+    // keyed on the signed code, so negative codes are told apart
+    let mut tagged: HashMap<i64, usize> = HashMap::new();
+    let mut tag_count = 0;
+    while idx < lines.len() {
+        let tag_idx = idx;
+        let tag = lines[tag_idx];
+        idx += 1;
+        if tag.trim().is_empty() {
+            continue;
+        }
+        tag_count += 1;
+
+        let signed = match FIGfont::parse_signed_code_tag(tag) {
+            Ok(code) => Some(code),
+            Err(_) => {
+                validator.push(tag_idx, Problem::InvalidCodeTag(tag.to_string()));
+                None
+            }
+        };
+        if let Some(code) = signed {
+            match tagged.get(&code) {
+                Some(&first_line) => {
+                    validator.push(tag_idx, Problem::DuplicateCodeTag { code, first_line })
+                }
+                None => {
+                    tagged.insert(code, tag_idx + 1);
+                }
+            }
+        }
+        // negative codes are allowed, they just can't be typed, so they're
+        // reported as character 0
+        let code = signed
+            .and_then(|code| u32::try_from(code).ok())
+            .unwrap_or(0);
+        let Some(next) = validator.check_character(idx, code) else {
+            return validator.diagnostics;
+        };
+        idx = next;
+    }
+
+    if let Some(declared) = header.codetag_count {
+        if declared as usize != tag_count {
+            validator.push(
+                0,
+                Problem::CodetagCountMismatch {
+                    declared: declared as usize,
+                    found: tag_count,
+                },
+            );
+        }
+    }
+
+    validator.diagnostics
+}
+
+/// Checks a font file for problems
+pub fn validate_file(path: impl AsRef<Path>) -> io::Result<Vec<Diagnostic>> {
+    Ok(validate(&read_source(path)?))
+}
+
+struct Validator<'a> {
+    lines: &'a [&'a str],
+    height: usize,
+    hardblank: char,
+    max_length: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator<'_> {
+    /// `idx` counts from 0, like the lines
+    fn push(&mut self, idx: usize, problem: Problem) {
+        self.diagnostics.push(Diagnostic {
+            line: idx + 1,
+            problem,
+        });
+    }
+
+    /// Whether the character starting at `idx` looks like the space, the
+    /// only one made of nothing but blanks
+    fn is_space(&self, idx: usize) -> bool {
+        let Some(rows) = self.lines.get(idx..idx + self.height) else {
+            return false;
+        };
+        let mut endmarks = rows.iter().map(|row| {
+            let row = row.trim_end();
+            let endmark = row.chars().last()?;
+            let art = row.trim_end_matches(endmark);
+            art.chars()
+                .all(|c| c == ' ' || c == self.hardblank)
+                .then_some(endmark)
+        });
+        match endmarks.next() {
+            Some(Some(first)) => endmarks.all(|endmark| endmark == Some(first)),
+            _ => false,
+        }
+    }
+
+    /// Checks the character starting at `idx`, returning where the next
+    /// one starts, or `None` if the file ends before the character does
+    fn check_character(&mut self, idx: usize, code: u32) -> Option<usize> {
+        // the comments can claim to run past the end of the file
+        let idx = idx.min(self.lines.len());
+        let end = (idx + self.height).min(self.lines.len());
+        let rows = &self.lines[idx..end];
+        if rows.len() < self.height {
+            self.push(
+                idx,
+                Problem::WrongHeight {
+                    code,
+                    expected: self.height,
+                    found: rows.len(),
+                },
+            );
+            return None;
+        }
+
+        let mut first: Option<(char, usize)> = None;
+        let mut reported_width = false;
+        let mut reported_endmark = false;
+        for (row_idx, row) in rows.iter().enumerate() {
+            let line = idx + row_idx;
+            if row.chars().count() > self.max_length {
+                self.push(
+                    line,
+                    Problem::RowTooLong {
+                        code,
+                        length: row.chars().count(),
+                        max_length: self.max_length,
+                    },
+                );
+            }
+
+            let row = row.trim_end();
+            let Some(endmark) = row.chars().last() else {
+                self.push(line, Problem::MissingEndmark { code });
+                continue;
+            };
+            let width = row.trim_end_matches(endmark).chars().count();
+
+            let Some((expected_endmark, expected_width)) = first else {
+                first = Some((endmark, width));
+                continue;
+            };
+            if endmark != expected_endmark && !reported_endmark {
+                reported_endmark = true;
+                self.push(
+                    line,
+                    Problem::InconsistentEndmark {
+                        code,
+                        expected: expected_endmark,
+                        found: endmark,
+                    },
+                );
+            } else if width != expected_width && !reported_width {
+                reported_width = true;
+                self.push(
+                    line,
+                    Problem::InconsistentWidth {
+                        code,
+                        expected: expected_width,
+                        found: width,
+                    },
+                );
+            }
+        }
+
+        // a doubled endmark before the last row means the character is
+        // shorter than the font
+        if let Some((endmark, _)) = first {
+            let doubled = format!("{endmark}{endmark}");
+            let ends = |row: &&str| row.trim_end().ends_with(&doubled);
+            if !ends(&rows[self.height - 1]) {
+                if let Some(short) = rows.iter().position(ends) {
+                    self.push(
+                        idx + short,
+                        Problem::WrongHeight {
+                            code,
+                            expected: self.height,
+                            found: short + 1,
+                        },
+                    );
+                }
+            }
+        }
+
+        Some(end)
+    }
+}
@@ -0,0 +1,176 @@
+use std::fmt;
+
+/// A single character of rendered output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    /// byte offset of the input character that produced this cell,
+    /// `None` for padding that no character drew
+    pub source: Option<usize>,
+    /// whether this cell is the result of smushing two characters together
+    pub smushed: bool,
+}
+
+impl Cell {
+    pub const BLANK: Cell = Cell {
+        ch: ' ',
+        source: None,
+        smushed: false,
+    };
+
+    pub fn new(ch: char, source: usize) -> Self {
+        Self {
+            ch,
+            source: Some(source),
+            smushed: false,
+        }
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.ch == ' '
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::BLANK
+    }
+}
+
+/// A character the font didn't have, and what was drawn instead
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Substitution {
+    /// byte offset of the character in the input
+    pub offset: usize,
+    pub original: char,
+    /// `None` if the character was skipped, `Some('\0')` for the font's
+    /// code 0 character
+    pub replacement: Option<char>,
+}
+
+/// Rendered FIGlet text as a grid of cells
+///
+/// Every cell remembers which input character it came from, so the
+/// output can be styled or hit-tested per character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FIGure {
+    text: String,
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    substitutions: Vec<Substitution>,
+}
+
+impl FIGure {
+    /// Creates a blank figure of the given size
+    pub fn new(text: impl Into<String>, width: usize, height: usize) -> Self {
+        Self {
+            text: text.into(),
+            width,
+            height,
+            cells: vec![Cell::BLANK; width * height],
+            substitutions: vec![],
+        }
+    }
+
+    /// Builds a figure from rows of cells, padding short rows with blanks
+    pub fn from_rows(text: impl Into<String>, rows: Vec<Vec<Cell>>) -> Self {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for mut row in rows {
+            row.resize(width, Cell::BLANK);
+            cells.append(&mut row);
+        }
+
+        Self {
+            text: text.into(),
+            width,
+            height,
+            cells,
+            substitutions: vec![],
+        }
+    }
+
+    pub fn with_substitutions(mut self, substitutions: Vec<Substitution>) -> Self {
+        self.substitutions = substitutions;
+        self
+    }
+
+    /// The text this figure was rendered from
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Characters of the input the font didn't have
+    pub fn substitutions(&self) -> &[Substitution] {
+        &self.substitutions
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn cell(&self, x: usize, y: usize) -> Option<&Cell> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.cells.get(y * self.width + x)
+    }
+
+    pub fn cell_mut(&mut self, x: usize, y: usize) -> Option<&mut Cell> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.cells.get_mut(y * self.width + x)
+    }
+
+    pub fn row(&self, y: usize) -> &[Cell] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// Iterates over every cell along with its position
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, &Cell)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (i % self.width, i / self.width, cell))
+    }
+
+    /// The byte offset of the input character drawn at this position
+    pub fn source_at(&self, x: usize, y: usize) -> Option<usize> {
+        self.cell(x, y).and_then(|cell| cell.source)
+    }
+
+    /// Every position drawn by the input character at `offset`
+    pub fn cells_from(&self, offset: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.cells()
+            .filter(move |(_, _, cell)| cell.source == Some(offset))
+            .map(|(x, y, _)| (x, y))
+    }
+}
+
+impl fmt::Display for FIGure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            // padding only exists to keep the grid rectangular
+            let len = row
+                .iter()
+                .rposition(|cell| cell.source.is_some() || !cell.is_blank())
+                .map_or(0, |x| x + 1);
+            for cell in &row[..len] {
+                write!(f, "{}", cell.ch)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
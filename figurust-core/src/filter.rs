@@ -0,0 +1,251 @@
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::figure::{Cell, FIGure};
+
+/// Transformations of rendered output, modelled on toilet's `-F` filters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    /// trims blank rows and columns from the edges
+    Crop,
+    /// mirrors horizontally
+    Flip,
+    /// mirrors vertically
+    Flop,
+    Rotate180,
+    /// rotates 90 degrees counter-clockwise
+    Left,
+    /// rotates 90 degrees clockwise
+    Right,
+    /// draws a frame around the output
+    Border,
+}
+
+#[derive(Debug, Error)]
+pub enum FilterParseError {
+    #[error("Unknown filter: {0:?}")]
+    UnknownFilter(String),
+}
+
+// pairs of characters that turn into each other under each transformation
+
+const FLIP_PAIRS: &[(char, char)] = &[
+    ('/', '\\'),
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('<', '>'),
+    ('`', '\''),
+    ('b', 'd'),
+    ('p', 'q'),
+];
+
+const FLOP_PAIRS: &[(char, char)] = &[
+    ('/', '\\'),
+    ('_', '‾'),
+    ('^', 'v'),
+    ('\'', ','),
+    ('`', '.'),
+    ('b', 'p'),
+    ('d', 'q'),
+    ('m', 'w'),
+];
+
+const ROTATE180_PAIRS: &[(char, char)] = &[
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('<', '>'),
+    ('_', '‾'),
+    ('^', 'v'),
+    ('\'', ','),
+    ('`', '.'),
+    ('b', 'q'),
+    ('d', 'p'),
+    ('m', 'w'),
+    ('n', 'u'),
+];
+
+// quarter turns map the same way in both directions
+const ROTATE90_PAIRS: &[(char, char)] = &[('/', '\\'), ('|', '-')];
+
+const BORDER_CORNERS: [char; 4] = ['.', '.', '\'', '\''];
+const BORDER_HORIZONTAL: char = '-';
+const BORDER_VERTICAL: char = '|';
+
+fn map_char(ch: char, pairs: &[(char, char)]) -> char {
+    for &(a, b) in pairs {
+        if ch == a {
+            return b;
+        }
+        if ch == b {
+            return a;
+        }
+    }
+    ch
+}
+
+fn map_cell(cell: &Cell, pairs: &[(char, char)]) -> Cell {
+    Cell {
+        ch: map_char(cell.ch, pairs),
+        ..*cell
+    }
+}
+
+impl FromStr for Filter {
+    type Err = FilterParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "crop" => Ok(Self::Crop),
+            "flip" => Ok(Self::Flip),
+            "flop" => Ok(Self::Flop),
+            "rotate180" | "180" => Ok(Self::Rotate180),
+            "left" => Ok(Self::Left),
+            "right" => Ok(Self::Right),
+            "border" => Ok(Self::Border),
+            _ => Err(FilterParseError::UnknownFilter(s.to_string())),
+        }
+    }
+}
+
+impl Filter {
+    /// Parses a colon separated list of filters like `crop:border`
+    pub fn parse_chain(s: &str) -> Result<Vec<Filter>, FilterParseError> {
+        s.split(':')
+            .filter(|name| !name.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    pub fn apply(self, figure: &FIGure) -> FIGure {
+        self.transform(figure)
+            .with_substitutions(figure.substitutions().to_vec())
+    }
+
+    fn transform(self, figure: &FIGure) -> FIGure {
+        let (width, height) = (figure.width(), figure.height());
+        let text = figure.text();
+
+        match self {
+            Self::Crop => {
+                let used: Vec<_> = figure
+                    .cells()
+                    .filter(|(_, _, cell)| !cell.is_blank())
+                    .map(|(x, y, _)| (x, y))
+                    .collect();
+                let Some(&(first_x, first_y)) = used.first() else {
+                    return FIGure::new(text, 0, 0);
+                };
+
+                let (mut left, mut right, mut top, mut bottom) =
+                    (first_x, first_x, first_y, first_y);
+                for &(x, y) in &used {
+                    left = left.min(x);
+                    right = right.max(x);
+                    top = top.min(y);
+                    bottom = bottom.max(y);
+                }
+
+                let rows = (top..=bottom)
+                    .map(|y| figure.row(y)[left..=right].to_vec())
+                    .collect();
+                FIGure::from_rows(text, rows)
+            }
+            Self::Flip => {
+                let rows = figure
+                    .rows()
+                    .map(|row| row.iter().rev().map(|c| map_cell(c, FLIP_PAIRS)).collect())
+                    .collect();
+                FIGure::from_rows(text, rows)
+            }
+            Self::Flop => {
+                let rows = (0..height)
+                    .rev()
+                    .map(|y| {
+                        figure
+                            .row(y)
+                            .iter()
+                            .map(|c| map_cell(c, FLOP_PAIRS))
+                            .collect()
+                    })
+                    .collect();
+                FIGure::from_rows(text, rows)
+            }
+            Self::Rotate180 => {
+                let rows = (0..height)
+                    .rev()
+                    .map(|y| {
+                        figure
+                            .row(y)
+                            .iter()
+                            .rev()
+                            .map(|c| map_cell(c, ROTATE180_PAIRS))
+                            .collect()
+                    })
+                    .collect();
+                FIGure::from_rows(text, rows)
+            }
+            Self::Left => {
+                let rows = (0..width)
+                    .rev()
+                    .map(|x| {
+                        (0..height)
+                            .map(|y| map_cell(&figure.row(y)[x], ROTATE90_PAIRS))
+                            .collect()
+                    })
+                    .collect();
+                FIGure::from_rows(text, rows)
+            }
+            Self::Right => {
+                let rows = (0..width)
+                    .map(|x| {
+                        (0..height)
+                            .rev()
+                            .map(|y| map_cell(&figure.row(y)[x], ROTATE90_PAIRS))
+                            .collect()
+                    })
+                    .collect();
+                FIGure::from_rows(text, rows)
+            }
+            Self::Border => {
+                let frame = |ch| Cell {
+                    ch,
+                    source: None,
+                    smushed: false,
+                };
+                let [top_left, top_right, bottom_left, bottom_right] = BORDER_CORNERS;
+
+                let mut edge = vec![frame(BORDER_HORIZONTAL); width + 2];
+                let mut rows = Vec::with_capacity(height + 2);
+
+                edge[0] = frame(top_left);
+                edge[width + 1] = frame(top_right);
+                rows.push(edge.clone());
+
+                for row in figure.rows() {
+                    let mut framed = Vec::with_capacity(width + 2);
+                    framed.push(frame(BORDER_VERTICAL));
+                    framed.extend_from_slice(row);
+                    framed.push(frame(BORDER_VERTICAL));
+                    rows.push(framed);
+                }
+
+                edge[0] = frame(bottom_left);
+                edge[width + 1] = frame(bottom_right);
+                rows.push(edge);
+
+                FIGure::from_rows(text, rows)
+            }
+        }
+    }
+}
+
+impl FIGure {
+    /// Runs `filters` over this figure in order
+    pub fn filtered(&self, filters: &[Filter]) -> FIGure {
+        filters
+            .iter()
+            .fold(self.clone(), |figure, filter| filter.apply(&figure))
+    }
+}
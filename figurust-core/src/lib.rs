@@ -0,0 +1,20 @@
+//! The font parsing and rendering behind figurust, in a crate of its own so
+//! figurust-macros can render with it too. Use it through figurust.
+
+pub mod figlet;
+pub mod figure;
+pub mod filter;
+pub mod markup;
+pub mod output;
+pub mod settings;
+pub mod style;
+
+#[cfg(test)]
+mod tests;
+
+#[macro_use]
+extern crate serde;
+
+pub const FIGLET_FONT_SIGNATURE: &str = "flf2";
+/// toilet's fonts are figlet fonts with a different signature
+pub const TOILET_FONT_SIGNATURE: &str = "tlf2";
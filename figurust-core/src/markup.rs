@@ -0,0 +1,191 @@
+use std::{ops::Range, str::FromStr, sync::Arc};
+
+use thiserror::Error;
+
+use crate::{
+    figlet::{lay_out, FIGfont, FontChain, FontConvertError, FontRegistry, RegistryError},
+    figure::FIGure,
+    settings::Settings,
+    style::{Color, ColorParseError, Style},
+};
+
+/// A run of text drawn in one font and color
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextSpan {
+    pub text: String,
+    /// the font's name, `None` for the font in the settings
+    pub font: Option<String>,
+    pub color: Option<Color>,
+}
+
+impl TextSpan {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_font(mut self, font: impl Into<String>) -> Self {
+        self.font = Some(font.into());
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// Text made of spans in different fonts and colors, rendered as one banner
+///
+/// It can be built span by span, or parsed from markup like
+/// `{font=small}v1.2 {font=big color=red}RELEASE`. Every tag starts a new
+/// span with only the attributes it lists, so `{}` goes back to the
+/// defaults. `{{` and `}}` are literal braces.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RichText {
+    pub spans: Vec<TextSpan>,
+}
+
+#[derive(Debug, Error)]
+pub enum MarkupError {
+    #[error("Tag at byte {0} is never closed")]
+    UnclosedTag(usize),
+    #[error("Unexpected '}}' at byte {0}")]
+    UnexpectedClose(usize),
+    #[error("Expected key=value, found {0:?}")]
+    InvalidAttribute(String),
+    #[error("Unknown attribute: {0:?}")]
+    UnknownAttribute(String),
+    #[error("Invalid color: {0}")]
+    InvalidColor(#[from] ColorParseError),
+}
+
+#[derive(Debug, Error)]
+pub enum RichTextError {
+    #[error("Failed to load font: {0}")]
+    RegistryError(#[from] RegistryError),
+    #[error("Failed to convert text: {0}")]
+    FontConvertError(#[from] FontConvertError),
+}
+
+impl FromStr for RichText {
+    type Err = MarkupError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rich_text = RichText::default();
+        let mut span = TextSpan::default();
+        let mut chars = s.char_indices().peekable();
+
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '{' if chars.next_if(|&(_, c)| c == '{').is_some() => span.text.push('{'),
+                '}' if chars.next_if(|&(_, c)| c == '}').is_some() => span.text.push('}'),
+                '}' => return Err(MarkupError::UnexpectedClose(idx)),
+                '{' => {
+                    let end = s[idx..].find('}').ok_or(MarkupError::UnclosedTag(idx))?;
+                    let tag = &s[idx + 1..idx + end];
+                    let next = parse_tag(tag)?;
+                    rich_text.push(std::mem::replace(&mut span, next));
+                    // skip to the end of the tag
+                    while chars.next_if(|&(i, _)| i <= idx + end).is_some() {}
+                }
+                c => span.text.push(c),
+            }
+        }
+        rich_text.push(span);
+
+        Ok(rich_text)
+    }
+}
+
+/// the attributes of a tag, without its braces
+fn parse_tag(tag: &str) -> Result<TextSpan, MarkupError> {
+    let mut span = TextSpan::default();
+    for attribute in tag.split_whitespace() {
+        let (key, value) = attribute
+            .split_once('=')
+            .ok_or_else(|| MarkupError::InvalidAttribute(attribute.to_string()))?;
+        match key {
+            "font" => span.font = Some(value.to_string()),
+            "color" => span.color = Some(value.parse()?),
+            _ => return Err(MarkupError::UnknownAttribute(key.to_string())),
+        }
+    }
+    Ok(span)
+}
+
+impl RichText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_span(mut self, span: TextSpan) -> Self {
+        self.push(span);
+        self
+    }
+
+    /// Adds a span to the end, empty ones are dropped
+    pub fn push(&mut self, span: TextSpan) {
+        if !span.text.is_empty() {
+            self.spans.push(span);
+        }
+    }
+
+    /// All of the text, without any markup
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    /// Each span along with the bytes of `text` it covers
+    pub fn ranges(&self) -> impl Iterator<Item = (Range<usize>, &TextSpan)> {
+        let mut start = 0;
+        self.spans.iter().map(move |span| {
+            let range = start..start + span.text.len();
+            start = range.end;
+            (range, span)
+        })
+    }
+
+    /// Colors each span as the markup asks, for use with the figure from
+    /// `render`
+    pub fn style(&self) -> Style {
+        self.ranges()
+            .filter_map(|(range, span)| Some((range, span.color?)))
+            .fold(Style::default(), |style, (range, color)| {
+                style.with_span(range, color)
+            })
+    }
+
+    /// Draws every span in its own font, falling back to the font in
+    /// `settings`, and joins them into one figure
+    pub fn render(
+        &self,
+        registry: &FontRegistry,
+        settings: &Settings,
+    ) -> Result<FIGure, RichTextError> {
+        let fonts = self
+            .spans
+            .iter()
+            .map(|span| registry.get(span.font.as_deref().unwrap_or(&settings.font)))
+            .collect::<Result<Vec<Arc<FIGfont>>, _>>()?;
+        let chains: Vec<FontChain> = fonts.iter().map(|font| FontChain::new(font)).collect();
+        let starts: Vec<usize> = self.ranges().map(|(range, _)| range.start).collect();
+
+        let text = self.text();
+        let layout = lay_out(&text, settings, true, |offset| {
+            let idx = starts.partition_point(|&start| start <= offset);
+            &chains[idx.saturating_sub(1)]
+        })?;
+
+        Ok(FIGure::from_rows(text, layout.rows).with_substitutions(layout.substitutions))
+    }
+
+    pub fn convert(
+        &self,
+        registry: &FontRegistry,
+        settings: &Settings,
+    ) -> Result<String, RichTextError> {
+        Ok(self.render(registry, settings)?.to_string())
+    }
+}
@@ -0,0 +1,89 @@
+use std::fmt::Write;
+
+use crate::{figure::FIGure, style::Style};
+
+#[derive(Debug, Clone)]
+pub struct HtmlSettings {
+    /// colors each run of text with an inline `<span>`
+    pub style: Option<Style>,
+    /// class attribute of the `<pre>` element
+    pub class: Option<String>,
+    /// label the banner with the text it was rendered from, so screen
+    /// readers don't try to read out the art
+    pub aria_label: bool,
+}
+
+impl Default for HtmlSettings {
+    fn default() -> Self {
+        Self {
+            style: None,
+            class: None,
+            aria_label: true,
+        }
+    }
+}
+
+/// Escapes the characters that are special in HTML text and attributes
+pub fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Renders `figure` as a `<pre>` block
+pub fn to_html(figure: &FIGure, settings: &HtmlSettings) -> String {
+    let mut output = String::from("<pre");
+
+    if let Some(class) = &settings.class {
+        let _ = write!(output, " class=\"{}\"", escape_html(class));
+    }
+
+    if settings.aria_label {
+        // attributes can't hold the line breaks of multiline input
+        let label = figure
+            .text()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = write!(
+            output,
+            " role=\"img\" aria-label=\"{}\"",
+            escape_html(&label)
+        );
+    }
+    output.push('>');
+
+    match &settings.style {
+        Some(style) => {
+            for row in style.runs(figure) {
+                for (run, color) in row {
+                    match color {
+                        Some(color) => {
+                            let _ = write!(
+                                output,
+                                "<span style=\"color:{}\">{}</span>",
+                                color.to_hex(),
+                                escape_html(&run)
+                            );
+                        }
+                        None => output.push_str(&escape_html(&run)),
+                    }
+                }
+                output.push('\n');
+            }
+        }
+        None => output.push_str(&escape_html(&figure.to_string())),
+    }
+
+    output.push_str("</pre>");
+    output
+}
@@ -0,0 +1,7 @@
+mod html;
+mod png;
+mod svg;
+
+pub use self::png::{rasterize, to_png, PngError, PngSettings, Raster, CELL_HEIGHT, CELL_WIDTH};
+pub use html::{escape_html, to_html, HtmlSettings};
+pub use svg::{to_svg, SvgSettings};
@@ -0,0 +1,156 @@
+use std::io::Write;
+
+use thiserror::Error;
+
+use crate::{
+    figure::FIGure,
+    style::{Color, Style},
+};
+
+// the 8x13 font from the X11 misc-fixed collection, which is public domain.
+// it's a 128 pixel wide, 1 bit per pixel atlas of the glyphs from 0x20 to
+// 0x7f, 16 glyphs per row, most significant bit first
+const CELL_FONT: &[u8] = include_bytes!("font_8x13.raw");
+const CELL_FONT_FIRST: u32 = 0x20;
+const CELL_FONT_LAST: u32 = 0x7f;
+const CELL_FONT_COLUMNS: usize = 16;
+
+pub const CELL_WIDTH: u32 = 8;
+pub const CELL_HEIGHT: u32 = 13;
+
+#[derive(Debug, Clone)]
+pub struct PngSettings {
+    /// every font pixel becomes a `scale` x `scale` square
+    pub scale: u32,
+    /// space around the text in cells
+    pub padding: u32,
+    pub foreground: Color,
+    /// transparent if not set
+    pub background: Option<Color>,
+    /// overrides `foreground` for any cells it colors
+    pub style: Option<Style>,
+}
+
+impl Default for PngSettings {
+    fn default() -> Self {
+        Self {
+            scale: 1,
+            padding: 1,
+            foreground: Color::rgb(0, 0, 0),
+            background: Some(Color::rgb(255, 255, 255)),
+            style: None,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PngError {
+    #[error("Failed to encode PNG: {0}")]
+    EncodingError(#[from] png::EncodingError),
+}
+
+/// An 8 bit RGBA image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Raster {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Raster {
+    pub fn new(width: u32, height: u32, fill: [u8; 4]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: fill.repeat((width * height) as usize),
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let idx = ((y * self.width + x) * 4) as usize;
+        self.pixels[idx..idx + 4].try_into().unwrap()
+    }
+
+    fn fill_rect(&mut self, x: u32, y: u32, size: u32, color: [u8; 4]) {
+        for py in y..y + size {
+            for px in x..x + size {
+                let idx = ((py * self.width + px) * 4) as usize;
+                self.pixels[idx..idx + 4].copy_from_slice(&color);
+            }
+        }
+    }
+
+    pub fn write_png(&self, writer: impl Write) -> Result<(), PngError> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        Ok(())
+    }
+}
+
+fn rgba(color: Color) -> [u8; 4] {
+    [color.r, color.g, color.b, 255]
+}
+
+/// Whether the pixel at (`x`, `y`) of the glyph for `ch` is set.
+/// Characters the font doesn't have are drawn as `?`.
+fn glyph_pixel(ch: char, x: u32, y: u32) -> bool {
+    let mut code = ch as u32;
+    if !(CELL_FONT_FIRST..=CELL_FONT_LAST).contains(&code) {
+        code = '?' as u32;
+    }
+
+    let idx = (code - CELL_FONT_FIRST) as usize;
+    let (gx, gy) = (idx % CELL_FONT_COLUMNS, idx / CELL_FONT_COLUMNS);
+    let row = gy * CELL_HEIGHT as usize + y as usize;
+    let byte = CELL_FONT[row * CELL_FONT_COLUMNS + gx];
+    byte & (0x80 >> x) != 0
+}
+
+/// Draws every cell of `figure` with the built-in bitmap font
+pub fn rasterize(figure: &FIGure, settings: &PngSettings) -> Raster {
+    let scale = settings.scale.max(1);
+    let cell_width = CELL_WIDTH * scale;
+    let cell_height = CELL_HEIGHT * scale;
+    let padding = settings.padding;
+
+    let width = (figure.width() as u32 + padding * 2) * cell_width;
+    let height = (figure.height() as u32 + padding * 2) * cell_height;
+    let background = settings.background.map_or([0; 4], rgba);
+    let mut raster = Raster::new(width, height, background);
+
+    let colors = settings.style.as_ref().map(|style| style.colors(figure));
+
+    for (x, y, cell) in figure.cells() {
+        if cell.is_blank() {
+            continue;
+        }
+
+        let color = colors
+            .as_ref()
+            .and_then(|colors| colors[y * figure.width() + x])
+            .unwrap_or(settings.foreground);
+        let color = rgba(color);
+
+        let left = (x as u32 + padding) * cell_width;
+        let top = (y as u32 + padding) * cell_height;
+        for gy in 0..CELL_HEIGHT {
+            for gx in 0..CELL_WIDTH {
+                if glyph_pixel(cell.ch, gx, gy) {
+                    raster.fill_rect(left + gx * scale, top + gy * scale, scale, color);
+                }
+            }
+        }
+    }
+
+    raster
+}
+
+/// Rasterizes `figure` and encodes it as a PNG
+pub fn to_png(figure: &FIGure, settings: &PngSettings) -> Result<Vec<u8>, PngError> {
+    let mut output = vec![];
+    rasterize(figure, settings).write_png(&mut output)?;
+    Ok(output)
+}
@@ -0,0 +1,113 @@
+use std::fmt::Write;
+
+use crate::{
+    figure::FIGure,
+    style::{Color, Style},
+};
+
+use super::escape_html;
+
+#[derive(Debug, Clone)]
+pub struct SvgSettings {
+    pub font_family: String,
+    /// in pixels
+    pub font_size: f32,
+    /// advance of one cell as a fraction of `font_size`, most monospace
+    /// fonts are close to 0.6
+    pub char_width: f32,
+    /// distance between rows as a fraction of `font_size`
+    pub line_height: f32,
+    /// space around the text in pixels
+    pub padding: f32,
+    pub foreground: Color,
+    /// transparent if not set
+    pub background: Option<Color>,
+    /// overrides `foreground` for any cells it colors
+    pub style: Option<Style>,
+}
+
+impl Default for SvgSettings {
+    fn default() -> Self {
+        Self {
+            font_family: "monospace".to_string(),
+            font_size: 16.0,
+            char_width: 0.6,
+            line_height: 1.2,
+            padding: 8.0,
+            foreground: Color::rgb(0, 0, 0),
+            background: None,
+            style: None,
+        }
+    }
+}
+
+/// Renders `figure` as a standalone SVG document, one `<text>` per row
+pub fn to_svg(figure: &FIGure, settings: &SvgSettings) -> String {
+    let cell_width = settings.font_size * settings.char_width;
+    let line_height = settings.font_size * settings.line_height;
+    let width = figure.width() as f32 * cell_width + settings.padding * 2.0;
+    let height = figure.height() as f32 * line_height + settings.padding * 2.0;
+
+    let mut output = String::new();
+    let _ = writeln!(
+        output,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">"
+    );
+    let _ = writeln!(output, "<title>{}</title>", escape_html(figure.text()));
+
+    if let Some(background) = settings.background {
+        let _ = writeln!(
+            output,
+            "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+            background.to_hex()
+        );
+    }
+
+    let _ = writeln!(
+        output,
+        "<g font-family=\"{}\" font-size=\"{}\" fill=\"{}\" xml:space=\"preserve\">",
+        escape_html(&settings.font_family),
+        settings.font_size,
+        settings.foreground.to_hex()
+    );
+
+    let rows = match &settings.style {
+        Some(style) => style.runs(figure),
+        None => figure
+            .to_string()
+            .lines()
+            .map(|line| vec![(line.to_string(), None)])
+            .collect(),
+    };
+
+    for (y, row) in rows.into_iter().enumerate() {
+        if row.iter().all(|(run, _)| run.trim().is_empty()) {
+            continue;
+        }
+
+        // the baseline sits roughly one font size below the top of the row
+        let _ = write!(
+            output,
+            "<text x=\"{}\" y=\"{}\">",
+            settings.padding,
+            settings.padding + y as f32 * line_height + settings.font_size
+        );
+        for (run, color) in row {
+            match color {
+                Some(color) => {
+                    let _ = write!(
+                        output,
+                        "<tspan fill=\"{}\">{}</tspan>",
+                        color.to_hex(),
+                        escape_html(&run)
+                    );
+                }
+                None => output.push_str(&escape_html(&run)),
+            }
+        }
+        output.push_str("</text>\n");
+    }
+
+    output.push_str("</g>\n</svg>\n");
+    output
+}
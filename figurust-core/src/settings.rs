@@ -17,6 +17,8 @@ use std::{
 use execute::Execute;
 use thiserror::Error;
 
+use crate::figlet::SearchPath;
+
 #[derive(Debug, Default)]
 pub enum Justify {
     Left,
@@ -50,14 +52,30 @@ pub enum Direction {
     Auto,
 }
 
+/// What to do with characters the font doesn't have
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MissingCharacterPolicy {
+    /// fail the whole render
+    #[default]
+    Error,
+    /// leave the character out
+    Skip,
+    /// use the font's code 0 character, like figlet does
+    CodeZero,
+    /// use this character instead
+    Replace(char),
+}
+
 #[derive(Debug)]
 pub struct Settings {
     pub font: String,
-    pub font_directory: PathBuf,
+    /// where to look for `font`
+    pub font_path: SearchPath,
 
     pub justify: Justify,
 
-    pub width: usize,
+    /// wrap lines to this many columns, `None` to never wrap
+    pub width: Option<usize>,
 
     pub mode: Mode,
 
@@ -70,6 +88,26 @@ pub struct Settings {
     pub info_code: i8,
 
     pub direction: Direction,
+
+    pub missing_character: MissingCharacterPolicy,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            font: "standard".to_string(),
+            font_path: SearchPath::default(),
+            justify: Justify::default(),
+            width: None,
+            mode: Mode::default(),
+            spacing: Spacing::default(),
+            overlap: false,
+            layout_mode: None,
+            info_code: 0,
+            direction: Direction::default(),
+            missing_character: MissingCharacterPolicy::default(),
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -98,16 +136,13 @@ pub fn figlet_default_settings() -> Result<Settings, DefaultSettingsError> {
     // SAFETY: if code is zero there will be exactly one line
     let font_directory = PathBuf::from(font_directory.lines().next().unwrap());
 
+    let mut font_path = SearchPath::default();
+    font_path.prepend(font_directory);
+
+    // figlet wraps at 80 columns unless told otherwise
     Ok(Settings {
-        font: "standard".to_string(),
-        font_directory,
-        justify: Justify::default(),
-        width: 80,
-        mode: Mode::default(),
-        spacing: Spacing::default(),
-        overlap: false,
-        layout_mode: None,
-        info_code: 0,
-        direction: Direction::default(),
+        font_path,
+        width: Some(80),
+        ..Default::default()
     })
 }
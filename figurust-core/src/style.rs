@@ -0,0 +1,379 @@
+use std::{fmt::Write, ops::Range, str::FromStr};
+
+use owo_colors::{AnsiColors, DynColors, OwoColorize, XtermColors};
+use thiserror::Error;
+
+use crate::figure::FIGure;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+#[derive(Debug, Error)]
+pub enum ColorParseError {
+    #[error("Invalid hex color: {0:?}")]
+    InvalidHex(String),
+    #[error("Unknown color name: {0:?}")]
+    UnknownName(String),
+}
+
+// the xterm defaults for the 16 basic colors, used to pick the closest one
+const ANSI_PALETTE: [(AnsiColors, Color); 16] = [
+    (AnsiColors::Black, Color::rgb(0, 0, 0)),
+    (AnsiColors::Red, Color::rgb(205, 0, 0)),
+    (AnsiColors::Green, Color::rgb(0, 205, 0)),
+    (AnsiColors::Yellow, Color::rgb(205, 205, 0)),
+    (AnsiColors::Blue, Color::rgb(0, 0, 238)),
+    (AnsiColors::Magenta, Color::rgb(205, 0, 205)),
+    (AnsiColors::Cyan, Color::rgb(0, 205, 205)),
+    (AnsiColors::White, Color::rgb(229, 229, 229)),
+    (AnsiColors::BrightBlack, Color::rgb(127, 127, 127)),
+    (AnsiColors::BrightRed, Color::rgb(255, 0, 0)),
+    (AnsiColors::BrightGreen, Color::rgb(0, 255, 0)),
+    (AnsiColors::BrightYellow, Color::rgb(255, 255, 0)),
+    (AnsiColors::BrightBlue, Color::rgb(92, 92, 255)),
+    (AnsiColors::BrightMagenta, Color::rgb(255, 0, 255)),
+    (AnsiColors::BrightCyan, Color::rgb(0, 255, 255)),
+    (AnsiColors::BrightWhite, Color::rgb(255, 255, 255)),
+];
+
+// the intensity of each step of the 6x6x6 xterm color cube
+const CUBE_STEPS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Color {
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Linearly interpolates towards `other`, `t` is clamped to `0.0..=1.0`
+    pub fn lerp(self, other: Color, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Self::rgb(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+        )
+    }
+
+    /// A fully saturated color, `hue` is in degrees
+    pub fn from_hue(hue: f32) -> Self {
+        let h = hue.rem_euclid(360.0) / 60.0;
+        let x = 1.0 - (h % 2.0 - 1.0).abs();
+        let (r, g, b) = match h as u8 {
+            0 => (1.0, x, 0.0),
+            1 => (x, 1.0, 0.0),
+            2 => (0.0, 1.0, x),
+            3 => (0.0, x, 1.0),
+            4 => (x, 0.0, 1.0),
+            _ => (1.0, 0.0, x),
+        };
+        let channel = |c: f32| (c * 255.0).round() as u8;
+        Self::rgb(channel(r), channel(g), channel(b))
+    }
+
+    /// `#rrggbb` notation
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
+    fn distance(self, other: Color) -> u32 {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+        d(self.r, other.r) + d(self.g, other.g) + d(self.b, other.b)
+    }
+
+    /// The closest of the 16 basic terminal colors
+    pub fn to_ansi16(self) -> AnsiColors {
+        ANSI_PALETTE
+            .iter()
+            .min_by_key(|(_, color)| self.distance(*color))
+            .map(|(ansi, _)| *ansi)
+            .unwrap()
+    }
+
+    /// The closest color in the xterm 256 color palette
+    pub fn to_ansi256(self) -> u8 {
+        let step = |c: u8| {
+            CUBE_STEPS
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, &s)| (s as i32 - c as i32).abs())
+                .map(|(x, _)| x as u8)
+                .unwrap()
+        };
+        let (r, g, b) = (step(self.r), step(self.g), step(self.b));
+        let cube = Color::rgb(
+            CUBE_STEPS[r as usize],
+            CUBE_STEPS[g as usize],
+            CUBE_STEPS[b as usize],
+        );
+
+        // the grayscale ramp runs from 8 to 238 in steps of 10
+        let avg = (self.r as u32 + self.g as u32 + self.b as u32) / 3;
+        let gray_idx = (avg.saturating_sub(3) / 10).min(23) as u8;
+        let gray_level = 8 + gray_idx * 10;
+        let gray = Color::rgb(gray_level, gray_level, gray_level);
+
+        if self.distance(gray) < self.distance(cube) {
+            232 + gray_idx
+        } else {
+            16 + 36 * r + 6 * g + b
+        }
+    }
+
+    fn to_dyn(self, support: ColorSupport) -> DynColors {
+        match support {
+            ColorSupport::TrueColor => DynColors::Rgb(self.r, self.g, self.b),
+            ColorSupport::Ansi256 => DynColors::Xterm(XtermColors::from(self.to_ansi256())),
+            _ => DynColors::Ansi(self.to_ansi16()),
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = ColorParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(hex) = s.strip_prefix('#') {
+            if hex.len() != 6 || !hex.is_ascii() {
+                return Err(ColorParseError::InvalidHex(s.to_string()));
+            }
+            let channel = |idx: usize| {
+                u8::from_str_radix(&hex[idx..idx + 2], 16)
+                    .map_err(|_| ColorParseError::InvalidHex(s.to_string()))
+            };
+            return Ok(Self::rgb(channel(0)?, channel(2)?, channel(4)?));
+        }
+
+        let color = match s.to_ascii_lowercase().as_str() {
+            "black" => Self::rgb(0, 0, 0),
+            "red" => Self::rgb(255, 0, 0),
+            "green" => Self::rgb(0, 255, 0),
+            "yellow" => Self::rgb(255, 255, 0),
+            "blue" => Self::rgb(0, 0, 255),
+            "magenta" => Self::rgb(255, 0, 255),
+            "cyan" => Self::rgb(0, 255, 255),
+            "white" => Self::rgb(255, 255, 255),
+            "gray" | "grey" => Self::rgb(128, 128, 128),
+            "orange" => Self::rgb(255, 165, 0),
+            "purple" => Self::rgb(128, 0, 128),
+            _ => return Err(ColorParseError::UnknownName(s.to_string())),
+        };
+        Ok(color)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GradientDirection {
+    Horizontal,
+    Vertical,
+    Diagonal,
+}
+
+/// How the cells of a figure are colored
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fill {
+    /// no color
+    None,
+    /// every cell the same color
+    Solid(Color),
+    /// one color per input character, cycling if there are fewer colors
+    /// than characters
+    Characters(Vec<Color>),
+    Gradient {
+        from: Color,
+        to: Color,
+        direction: GradientDirection,
+    },
+    /// a diagonal rainbow across the whole figure
+    Rainbow,
+}
+
+/// Colors an explicit range of input bytes, on top of the fill
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub range: Range<usize>,
+    pub color: Color,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    pub fill: Fill,
+    /// later spans take precedence over earlier ones
+    pub spans: Vec<Span>,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self::new(Fill::None)
+    }
+}
+
+/// What kind of colors the output device understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Guesses the color support of the terminal from the environment,
+    /// honouring `NO_COLOR` (https://no-color.org)
+    pub fn detect() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|val| !val.is_empty());
+
+        if var("NO_COLOR").is_some() {
+            return Self::None;
+        }
+
+        if let Some(colorterm) = var("COLORTERM") {
+            if colorterm == "truecolor" || colorterm == "24bit" {
+                return Self::TrueColor;
+            }
+        }
+
+        match var("TERM") {
+            Some(term) if term == "dumb" => Self::None,
+            Some(term) if term.contains("256color") => Self::Ansi256,
+            _ => Self::Ansi16,
+        }
+    }
+}
+
+impl Style {
+    pub fn new(fill: Fill) -> Self {
+        Self {
+            fill,
+            spans: vec![],
+        }
+    }
+
+    pub fn solid(color: Color) -> Self {
+        Self::new(Fill::Solid(color))
+    }
+
+    pub fn characters(colors: Vec<Color>) -> Self {
+        Self::new(Fill::Characters(colors))
+    }
+
+    pub fn gradient(from: Color, to: Color, direction: GradientDirection) -> Self {
+        Self::new(Fill::Gradient {
+            from,
+            to,
+            direction,
+        })
+    }
+
+    pub fn rainbow() -> Self {
+        Self::new(Fill::Rainbow)
+    }
+
+    pub fn with_span(mut self, range: Range<usize>, color: Color) -> Self {
+        self.spans.push(Span { range, color });
+        self
+    }
+
+    /// Works out the color of every cell in `figure`, row by row.
+    /// Blank cells are never colored.
+    pub fn colors(&self, figure: &FIGure) -> Vec<Option<Color>> {
+        // byte offset -> character index, for `Fill::Characters`
+        let offsets: Vec<usize> = figure.text().char_indices().map(|(idx, _)| idx).collect();
+
+        let width = figure.width().saturating_sub(1).max(1) as f32;
+        let height = figure.height().saturating_sub(1).max(1) as f32;
+
+        figure
+            .cells()
+            .map(|(x, y, cell)| {
+                if cell.is_blank() {
+                    return None;
+                }
+
+                if let Some(source) = cell.source {
+                    let span = self.spans.iter().rev().find(|s| s.range.contains(&source));
+                    if let Some(span) = span {
+                        return Some(span.color);
+                    }
+                }
+
+                let (x, y) = (x as f32, y as f32);
+                match &self.fill {
+                    Fill::None => None,
+                    Fill::Solid(color) => Some(*color),
+                    Fill::Characters(colors) => {
+                        let idx = offsets.binary_search(&cell.source?).ok()?;
+                        colors.get(idx % colors.len().max(1)).copied()
+                    }
+                    Fill::Gradient {
+                        from,
+                        to,
+                        direction,
+                    } => {
+                        let t = match direction {
+                            GradientDirection::Horizontal => x / width,
+                            GradientDirection::Vertical => y / height,
+                            GradientDirection::Diagonal => (x + y) / (width + height),
+                        };
+                        Some(from.lerp(*to, t))
+                    }
+                    Fill::Rainbow => Some(Color::from_hue((x + y) / (width + height) * 300.0)),
+                }
+            })
+            .collect()
+    }
+
+    /// Splits each line of `figure` into runs of text that share a color,
+    /// so outputs only have to switch colors where they actually change
+    pub fn runs(&self, figure: &FIGure) -> Vec<Vec<(String, Option<Color>)>> {
+        let colors = self.colors(figure);
+
+        figure
+            .to_string()
+            .lines()
+            .enumerate()
+            .map(|(y, line)| {
+                let row = &colors[y * figure.width()..];
+                let chars: Vec<char> = line.chars().collect();
+
+                let mut runs = vec![];
+                let mut start = 0;
+                while start < chars.len() {
+                    let color = row[start];
+                    let len = row[start..chars.len()]
+                        .iter()
+                        .take_while(|&&c| c == color)
+                        .count();
+                    runs.push((chars[start..start + len].iter().collect(), color));
+                    start += len;
+                }
+                runs
+            })
+            .collect()
+    }
+
+    /// Renders `figure` with ANSI color escapes suited to `support`
+    pub fn paint(&self, figure: &FIGure, support: ColorSupport) -> String {
+        if support == ColorSupport::None {
+            return figure.to_string();
+        }
+
+        let mut output = String::new();
+        for row in self.runs(figure) {
+            for (run, color) in row {
+                match color {
+                    Some(color) => {
+                        let _ = write!(output, "{}", run.color(color.to_dyn(support)));
+                    }
+                    None => output.push_str(&run),
+                }
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+}
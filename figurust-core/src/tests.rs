@@ -0,0 +1,1095 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Once,
+    },
+};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, trace, warn, LevelFilter};
+
+use crate::{
+    figlet::{
+        layout::{Extent, Glyph, LineLayout, PairCache},
+        validate, EmbeddedFont, FIGcharacter, FIGfont, FontChain, FontConvertError, FontLoadError,
+        FontRegistry, Problem, RawHeader, RegistryError, RowProfile, SearchPath, Severity,
+    },
+    figure::Substitution,
+    filter::Filter,
+    markup::{MarkupError, RichText, TextSpan},
+    output::{
+        rasterize, to_html, to_png, to_svg, HtmlSettings, PngSettings, SvgSettings, CELL_HEIGHT,
+        CELL_WIDTH,
+    },
+    settings::{self, Direction, Justify, MissingCharacterPolicy, Mode, Settings, Spacing},
+    style::{Color, ColorSupport, GradientDirection, Style},
+};
+
+mod small;
+
+static INIT: Once = Once::new();
+
+fn init() {
+    INIT.call_once(|| {
+        env_logger::builder()
+            .filter_level(LevelFilter::Trace)
+            .is_test(false)
+            .init();
+    });
+}
+
+fn load_small() -> FIGfont {
+    let small_font = include_str!("../fonts/small.flf");
+    small_font.parse::<FIGfont>().expect("failed to parse font")
+}
+
+// a fresh directory no other test, or other run of the tests, is using
+fn temp_dir(name: &str) -> PathBuf {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let count = COUNT.fetch_add(1, Ordering::Relaxed);
+    let dir = std::env::temp_dir().join(format!("figurust-{name}-{}-{count}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("failed to create temp directory");
+    dir
+}
+
+fn small_settings() -> Settings {
+    Settings {
+        font: "small".to_string(),
+        font_path: SearchPath::new(["fonts"]),
+        ..Default::default()
+    }
+}
+
+#[test]
+pub fn test_parse_header() {
+    init();
+
+    use small::*;
+    let small_font = include_str!("../fonts/small.flf");
+    let header_line = small_font
+        .lines()
+        .next()
+        .expect("small.flf has no first line");
+
+    let header = header_line
+        .parse::<RawHeader>()
+        .expect("failed to parse header");
+
+    assert_eq!(header.signature, SMALL_SIGNATURE);
+    assert_eq!(header.hardblank, SMALL_HARDBLANK);
+
+    assert_eq!(header.height, SMALL_HEIGHT);
+    assert_eq!(header.baseline, SMALL_BASELINE);
+    assert_eq!(header.max_length, SMALL_MAX_LENGTH);
+    assert_eq!(header.old_layout, SMALL_OLD_LAYOUT);
+    assert_eq!(header.comment_lines, SMALL_COMMENT_LINES);
+
+    assert_eq!(header.direction, SMALL_DIRECTION);
+    assert_eq!(header.full_layout, SMALL_FULL_LAYOUT);
+    assert_eq!(header.codetag_count, SMALL_CODETAG_COUNT);
+}
+
+#[test]
+pub fn test_parse_characters() {
+    init();
+
+    let small_font = include_str!("../fonts/small.flf");
+    let _ = small_font.parse::<FIGfont>().expect("failed to parse font");
+}
+
+#[test]
+pub fn test_convert_text() {
+    init();
+
+    let small_font = include_str!("../fonts/small.flf");
+    let font = small_font.parse::<FIGfont>().expect("failed to parse font");
+    let mut settings =
+        settings::figlet_default_settings().expect("failed to generate default settings");
+
+    // currently the only implemented spacing mode
+    settings.spacing = Spacing::FullWidth;
+
+    let text = "Hello, world!";
+    let converted = font
+        .convert(text, &settings)
+        .expect("failed to convert text");
+
+    let expected = r#"
+  _  _         _   _                                    _      _   _ 
+ | || |  ___  | | | |  ___        __ __ __  ___   _ _  | |  __| | | |
+ | __ | / -_) | | | | / _ \  _    \ V  V / / _ \ | '_| | | / _` | |_|
+ |_||_| \___| |_| |_| \___/ ( )    \_/\_/  \___/ |_|   |_| \__,_| (_)
+                            |/                                       
+"#;
+
+    assert_eq!(converted, expected[1..]);
+}
+
+#[test]
+pub fn test_convert_smushed() {
+    init();
+
+    let font = load_small();
+    let settings = small_settings();
+
+    let converted = font
+        .convert("Hello, world!", &settings)
+        .expect("failed to convert text");
+
+    let expected = r#"
+ _  _     _ _                        _    _ _ 
+| || |___| | |___    __ __ _____ _ _| |__| | |
+| __ / -_) | / _ \_  \ V  V / _ \ '_| / _` |_|
+|_||_\___|_|_\___( )  \_/\_/\___/_| |_\__,_(_)
+                 |/                           
+"#;
+
+    assert_eq!(converted, expected[1..]);
+}
+
+#[test]
+pub fn test_render_figure() {
+    init();
+
+    let font = load_small();
+    let settings = small_settings();
+
+    let text = "Hi\nFIG";
+    let figure = font.render(text, &settings).expect("failed to render text");
+
+    assert_eq!(figure.text(), text);
+    assert_eq!(figure.height(), 10);
+    assert_eq!(figure.to_string(), font.convert(text, &settings).unwrap());
+
+    // the 'H' and 'i' smush into each other
+    assert!(figure.cells().any(|(_, _, cell)| cell.smushed));
+    assert_eq!(figure.source_at(1, 1), Some(0));
+    assert!(figure.cells_from(1).all(|(_, y)| y < 5));
+
+    // 'F' starts the second line, byte 3 of the input
+    assert_eq!(figure.source_at(1, 6), Some(3));
+    assert!(figure.cells_from(3).all(|(_, y)| y >= 5));
+
+    // the first line is narrower, so it gets padded
+    let padding = figure.row(0).last().unwrap();
+    assert_eq!(padding.source, None);
+}
+
+#[test]
+pub fn test_style_colors() {
+    init();
+
+    let font = load_small();
+    let figure = font
+        .render("Hi", &small_settings())
+        .expect("failed to render text");
+
+    let red = Color::rgb(255, 0, 0);
+    let blue = Color::rgb(0, 0, 255);
+
+    // the span wins over the fill for the 'i'
+    let style = Style::solid(red).with_span(1..2, blue);
+    let colors = style.colors(&figure);
+    for (x, y, cell) in figure.cells() {
+        let color = colors[y * figure.width() + x];
+        match cell.source {
+            _ if cell.is_blank() => assert_eq!(color, None),
+            Some(0) => assert_eq!(color, Some(red)),
+            Some(1) => assert_eq!(color, Some(blue)),
+            _ => unreachable!(),
+        }
+    }
+
+    let gradient = Style::gradient(red, blue, GradientDirection::Horizontal);
+    assert_eq!(red.lerp(blue, 0.0), red);
+    assert_eq!(red.lerp(blue, 1.0), blue);
+    assert!(gradient.colors(&figure).iter().flatten().any(|c| *c != red));
+
+    assert_eq!("#ff0000".parse::<Color>().unwrap(), red);
+    assert_eq!("Blue".parse::<Color>().unwrap(), blue);
+    assert_eq!(red.to_ansi256(), 196);
+    assert_eq!(Color::rgb(8, 8, 8).to_ansi256(), 232);
+
+    // no color support is just the plain text
+    assert_eq!(style.paint(&figure, ColorSupport::None), figure.to_string());
+    let painted = style.paint(&figure, ColorSupport::TrueColor);
+    assert!(painted.contains("\x1b[38;2;255;0;0m"));
+    assert!(painted.contains("\x1b[38;2;0;0;255m"));
+    let painted = style.paint(&figure, ColorSupport::Ansi16);
+    assert!(painted.contains("\x1b[91m"));
+}
+
+#[test]
+pub fn test_html_output() {
+    init();
+
+    let font = load_small();
+    let figure = font
+        .render("<&>", &small_settings())
+        .expect("failed to render text");
+
+    let html = to_html(&figure, &HtmlSettings::default());
+    assert!(html.starts_with("<pre role=\"img\" aria-label=\"&lt;&amp;&gt;\">"));
+    assert!(html.ends_with("</pre>"));
+    // the art itself is full of slashes and brackets
+    let body = &html[html.find('>').unwrap() + 1..html.len() - "</pre>".len()];
+    assert!(!body.contains('<'));
+    assert_eq!(
+        body.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'"),
+        figure.to_string()
+    );
+
+    let settings = HtmlSettings {
+        style: Some(Style::solid(Color::rgb(255, 0, 0))),
+        class: Some("banner".to_string()),
+        aria_label: false,
+    };
+    let html = to_html(&figure, &settings);
+    assert!(html.starts_with("<pre class=\"banner\">"));
+    assert!(html.contains("<span style=\"color:#ff0000\">"));
+}
+
+#[test]
+pub fn test_svg_output() {
+    init();
+
+    let font = load_small();
+    let figure = font
+        .render("Hi", &small_settings())
+        .expect("failed to render text");
+
+    let settings = SvgSettings {
+        background: Some(Color::rgb(255, 255, 255)),
+        ..Default::default()
+    };
+    let svg = to_svg(&figure, &settings);
+
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.contains("<title>Hi</title>"));
+    assert!(svg.contains("<rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>"));
+    // the bottom row of the small font is blank
+    assert_eq!(svg.matches("<text ").count(), figure.height() - 1);
+    assert!(svg.trim_end().ends_with("</svg>"));
+}
+
+#[test]
+pub fn test_png_output() {
+    init();
+
+    let font = load_small();
+    let figure = font
+        .render("Hi", &small_settings())
+        .expect("failed to render text");
+
+    let red = Color::rgb(255, 0, 0);
+    let settings = PngSettings {
+        scale: 2,
+        padding: 0,
+        foreground: red,
+        background: None,
+        style: None,
+    };
+    let raster = rasterize(&figure, &settings);
+
+    assert_eq!(raster.width, figure.width() as u32 * CELL_WIDTH * 2);
+    assert_eq!(raster.height, figure.height() as u32 * CELL_HEIGHT * 2);
+    assert_eq!(
+        raster.pixels.len(),
+        (raster.width * raster.height * 4) as usize
+    );
+    assert!(raster.pixels.chunks(4).any(|px| px == [255, 0, 0, 255]));
+    // the top left cell of "Hi" is blank
+    assert_eq!(raster.pixel(0, 0), [0, 0, 0, 0]);
+
+    let png = to_png(&figure, &settings).expect("failed to encode png");
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+}
+
+#[test]
+pub fn test_filters() {
+    init();
+
+    let font = load_small();
+    let figure = font
+        .render("Hi", &small_settings())
+        .expect("failed to render text");
+
+    // the bottom row of the small font is blank
+    let cropped = Filter::Crop.apply(&figure);
+    assert_eq!(cropped.height(), figure.height() - 1);
+    assert_eq!(cropped.width(), figure.width());
+
+    for filter in [Filter::Flip, Filter::Flop, Filter::Rotate180] {
+        assert_eq!(figure.filtered(&[filter, filter]), figure);
+    }
+    assert_eq!(figure.filtered(&[Filter::Left, Filter::Right]), figure);
+
+    let left = Filter::Left.apply(&figure);
+    assert_eq!(left.width(), figure.height());
+    assert_eq!(left.height(), figure.width());
+
+    let flipped = Filter::Flip.apply(&cropped);
+    assert_eq!(flipped.to_string().lines().nth(1), Some("(_) || |"));
+
+    let filters = Filter::parse_chain("crop:border").expect("failed to parse filters");
+    let bordered = figure.filtered(&filters);
+    assert_eq!(bordered.width(), cropped.width() + 2);
+    assert_eq!(bordered.height(), cropped.height() + 2);
+    assert!(bordered.to_string().starts_with(".---"));
+    assert!(Filter::parse_chain("crop:sideways").is_err());
+}
+
+#[test]
+pub fn test_measure() {
+    init();
+
+    let font = load_small();
+    let mut settings = small_settings();
+
+    for spacing in [
+        Spacing::Smushing,
+        Spacing::ForceSmushing,
+        Spacing::Kerning,
+        Spacing::FullWidth,
+    ] {
+        settings.spacing = spacing;
+        for text in ["Hello, world!", "FIG\nure", " /\\_/\\ ", "", "a\n\nb"] {
+            let figure = font.render(text, &settings).expect("failed to render text");
+            let size = font
+                .measure(text, &settings)
+                .expect("failed to measure text");
+            assert_eq!(
+                size.width,
+                figure.width(),
+                "{text:?} {:?}",
+                settings.spacing
+            );
+            assert_eq!(
+                size.height,
+                figure.height(),
+                "{text:?} {:?}",
+                settings.spacing
+            );
+        }
+    }
+
+    settings.spacing = Spacing::default();
+    let width = font.measure("Hello", &settings).unwrap().width;
+    let fonts = [font];
+    assert!(FIGfont::largest_fitting(&fonts, "Hello", &settings, width).is_some());
+    assert!(FIGfont::largest_fitting(&fonts, "Hello", &settings, width - 1).is_none());
+    assert!(FIGfont::largest_fitting(&fonts, "Hello\u{1F600}", &settings, width).is_none());
+}
+
+#[test]
+pub fn test_glyph_profiles() {
+    init();
+
+    let font = load_small();
+    let character = &font.characters[&('H' as u32)];
+    assert_eq!(character.profile.len(), character.char_lines.len());
+    assert_eq!(
+        character.profile[0],
+        RowProfile {
+            left: 2,
+            first: Some('_'),
+            last: Some((5, '_')),
+        }
+    );
+    assert_eq!(character.profile[1].first, Some('|'));
+    assert_eq!(character.profile[1].last, Some((6, '|')));
+    assert_eq!(
+        character.profile[4],
+        RowProfile {
+            left: 7,
+            first: None,
+            last: None,
+        }
+    );
+
+    // profiles aren't stored, so building a character from its rows has to
+    // come up with the same ones
+    let rebuilt = FIGcharacter::new(character.char_lines.clone());
+    assert_eq!(rebuilt.width, character.width);
+    assert_eq!(rebuilt.profile, character.profile);
+
+    // endmarks only come from the font file
+    assert_eq!(character.endmarks, ["@", "@", "@", "@", "@@"]);
+    assert!(rebuilt.endmarks.is_empty());
+}
+
+#[test]
+pub fn test_font_registry() {
+    init();
+
+    let dir = temp_dir("registry");
+    std::fs::copy("fonts/small.flf", dir.join("Small.flf")).expect("failed to copy font");
+
+    let registry = FontRegistry::new([&dir]);
+    assert_eq!(registry.names(), ["Small"]);
+    let font = registry.get("small").expect("failed to get font");
+    let again = registry.get("SMALL.flf").expect("failed to get font");
+    assert!(std::sync::Arc::ptr_eq(&font, &again));
+    assert!(matches!(
+        registry.get("box"),
+        Err(RegistryError::NotFound { .. })
+    ));
+
+    let settings = small_settings();
+    let expected = font.convert("Hi", &settings).unwrap();
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                let font = registry.get("small").unwrap();
+                assert_eq!(font.convert("Hi", &settings).unwrap(), expected);
+            });
+        }
+    });
+
+    std::fs::write(dir.join("box.flf"), box_font("box")).expect("failed to write font");
+    registry.rescan();
+    assert_eq!(registry.names(), ["box", "Small"]);
+    assert!(registry.get("box").is_ok());
+    // fonts that didn't change stay loaded
+    assert!(std::sync::Arc::ptr_eq(
+        &font,
+        &registry.get("small").unwrap()
+    ));
+
+    // a truncated font is an error, and asking for it again still is
+    let truncated = box_font("box")
+        .lines()
+        .take(50)
+        .collect::<Vec<_>>()
+        .join("\n");
+    std::fs::write(dir.join("truncated.flf"), truncated).expect("failed to write font");
+    registry.rescan();
+    for _ in 0..2 {
+        assert!(matches!(
+            registry.get("truncated"),
+            Err(RegistryError::LoadError {
+                source: FontLoadError::Truncated,
+                ..
+            })
+        ));
+    }
+    assert!(matches!(
+        "flf2a$ 2 1 5 -1 50\nshort\n".parse::<FIGfont>(),
+        Err(FontLoadError::Truncated)
+    ));
+
+    // toilet fonts are listed and looked up like figlet fonts
+    let toilet = box_font("toilet").replacen("flf2a", "tlf2a", 1);
+    std::fs::write(dir.join("toilet.tlf"), toilet).expect("failed to write font");
+    registry.rescan();
+    assert!(registry.names().contains(&"toilet".to_string()));
+    assert!(registry.get("toilet").is_ok());
+    assert!(registry.get("Toilet.tlf").is_ok());
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+pub fn test_search_path() {
+    init();
+
+    let root = temp_dir("search-path");
+    let (project, system) = (root.join("project"), root.join("system"));
+    for dir in [&project, &system] {
+        std::fs::create_dir_all(dir).expect("failed to create font directory");
+        std::fs::write(dir.join("box.flf"), box_font(&dir.to_string_lossy()))
+            .expect("failed to write font");
+    }
+
+    // the first directory with the font wins
+    let mut search_path = SearchPath::new([&system]);
+    search_path.prepend(&project);
+    search_path.prepend(&system);
+    search_path.prepend(&project);
+    assert_eq!(search_path.directories, [project.clone(), system.clone()]);
+    let registry = search_path.registry();
+    assert_eq!(registry.path("box"), Some(project.join("box.flf")));
+    let font = registry.get("box").expect("failed to get font");
+    assert_eq!(font.comments, project.to_string_lossy());
+
+    // bundled fonts come last
+    assert!(!registry.contains("small"));
+    search_path.bundled = true;
+    let settings = Settings {
+        font: "small".to_string(),
+        font_path: search_path.clone(),
+        ..Default::default()
+    };
+    let font = settings.load_font().expect("failed to load bundled font");
+    assert_eq!(
+        font.convert("Hi", &settings).unwrap(),
+        load_small().convert("Hi", &settings).unwrap()
+    );
+    assert_eq!(search_path.registry().path("small"), None);
+
+    match search_path.registry().get("missing") {
+        Err(err @ RegistryError::NotFound { .. }) => {
+            let message = err.to_string();
+            for candidate in [project.join("missing.flf"), system.join("missing.flf")] {
+                assert!(message.contains(&*candidate.to_string_lossy()), "{message}");
+            }
+        }
+        other => panic!("expected NotFound, got {other:?}"),
+    }
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+pub fn test_font_chain() {
+    init();
+
+    let boxes = box_font("ascii only")
+        .parse::<FIGfont>()
+        .expect("failed to parse font");
+    let small = load_small();
+    let chain = FontChain::new(&boxes).with_fallback(&small);
+    let mut settings = small_settings();
+    settings.spacing = Spacing::FullWidth;
+
+    // the box font sits on the small font's baseline
+    let figure = chain
+        .render("a\u{e9}", &settings)
+        .expect("failed to render");
+    assert!(figure.substitutions().is_empty());
+    assert_eq!(figure.height(), 5);
+    let accent = small.render("\u{e9}", &settings).unwrap().to_string();
+    let lines: Vec<&str> = accent.lines().collect();
+    let expected: Vec<String> = ["   ", "   ", "╔═╗", "╚═╝", "   "]
+        .iter()
+        .zip(&lines)
+        .map(|(left, right)| format!("{left}{right}"))
+        .collect();
+    assert_eq!(figure.to_string().lines().collect::<Vec<_>>(), expected);
+
+    // lines are only as tall as the fonts they use
+    let converted = chain.convert("a\nb", &settings).unwrap();
+    assert_eq!(converted, "╔═╗\n╚═╝\n╔═╗\n╚═╝\n");
+    let size = chain.measure("a\u{e9}\nb", &settings).unwrap();
+    assert_eq!((size.width, size.height), (figure.width(), 7));
+
+    assert!(matches!(
+        chain.render("\u{2603}", &settings),
+        Err(FontConvertError::MissingCharacter('\u{2603}'))
+    ));
+}
+
+#[test]
+pub fn test_rich_text() {
+    init();
+
+    let markup = "{font=box color=red}a{}{{H}}".parse::<RichText>().unwrap();
+    let red = Color::rgb(255, 0, 0);
+    assert_eq!(
+        markup,
+        RichText::new()
+            .with_span(TextSpan::new("a").with_font("box").with_color(red))
+            .with_span(TextSpan::new("{H}"))
+    );
+    assert_eq!(markup.text(), "a{H}");
+
+    for (bad, expected) in [
+        ("{font}", "InvalidAttribute"),
+        ("{size=2}", "UnknownAttribute"),
+        ("{color=nope}", "InvalidColor"),
+        ("{font=small", "UnclosedTag"),
+        ("a}", "UnexpectedClose"),
+    ] {
+        let err: MarkupError = bad.parse::<RichText>().unwrap_err();
+        assert!(
+            format!("{err:?}").starts_with(expected),
+            "{bad:?} gave {err:?}"
+        );
+    }
+
+    let dir = temp_dir("rich-text");
+    std::fs::write(dir.join("box.flf"), box_font("box")).expect("failed to write font");
+    let registry = FontRegistry::new([&dir, &PathBuf::from("fonts")]);
+    let settings = small_settings();
+
+    let markup = "{font=box color=red}a{}H".parse::<RichText>().unwrap();
+    let figure = markup
+        .render(&registry, &settings)
+        .expect("failed to render");
+    // the box sits on the small font's baseline
+    assert_eq!(figure.height(), 5);
+    assert!(figure
+        .cells()
+        .filter(|(_, _, cell)| cell.source == Some(0) && !cell.is_blank())
+        .all(|(_, y, _)| (2..4).contains(&y)));
+    assert_eq!(figure.row(2)[0].ch, '╔');
+    assert!(figure.cells_from(1).count() > 0);
+
+    let colors = markup.style().colors(&figure);
+    for (x, y, cell) in figure.cells() {
+        if cell.source == Some(0) && !cell.is_blank() {
+            assert_eq!(colors[y * figure.width() + x], Some(red));
+        }
+    }
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+pub fn test_line_settings() {
+    init();
+
+    let font = load_small();
+    let mut settings = small_settings();
+    let one_line = font.render("Hello world", &settings).unwrap();
+
+    // nothing wraps unless a width is set
+    let long = "Hello world ".repeat(10);
+    let unwrapped = font.render(&long, &settings).unwrap();
+    assert_eq!(unwrapped.height(), 5);
+    assert!(unwrapped.width() > 80);
+    let measured = font.measure(&long, &settings).unwrap();
+    assert_eq!(measured.width, unwrapped.width());
+
+    // lines wrap at the last space that fits
+    settings.width = Some(one_line.width());
+    let wrapped = font.render("Hello world", &settings).unwrap();
+    assert_eq!(wrapped.height(), 10);
+    assert_eq!(
+        wrapped.to_string(),
+        font.convert("Hello\nworld", &settings).unwrap()
+    );
+    let measured = font.measure("Hello world", &settings).unwrap();
+    assert_eq!((measured.width, measured.height), (wrapped.width(), 10));
+
+    settings.width = Some(60);
+    settings.justify = Justify::Right;
+    let right = font.render("Hi", &settings).unwrap();
+    assert_eq!(right.width(), 59);
+    settings.justify = Justify::Center;
+    let center = font.render("Hi", &settings).unwrap();
+    // like figlet, the extra column of odd space goes on the left
+    let hi_width = font.render("Hi", &small_settings()).unwrap().width();
+    assert_eq!(hi_width, 8);
+    let padding = 26;
+    assert!(center.row(1)[..padding]
+        .iter()
+        .all(|cell| cell.source.is_none()));
+    assert!(center.row(1)[padding].source.is_some());
+
+    settings.justify = Justify::Left;
+    settings.direction = Direction::RightToLeft;
+    assert_eq!(
+        font.convert("ab", &settings).unwrap(),
+        font.convert("ba", &small_settings()).unwrap()
+    );
+
+    let mut settings = small_settings();
+    settings.mode = Mode::Paragraph;
+    let joined = font.convert("a\nb", &settings).unwrap();
+    assert_eq!(joined, font.convert("a b", &small_settings()).unwrap());
+    assert_eq!(font.render("a\n\nb", &settings).unwrap().height(), 15);
+    assert_eq!(font.render("a\n b", &settings).unwrap().height(), 10);
+}
+
+#[test]
+pub fn test_validate() {
+    init();
+
+    assert!(validate(include_str!("../fonts/small.flf")).is_empty());
+    // max_length counts the endmarks too
+    let font = box_font("box").replacen("flf2a$ 2 2 4", "flf2a$ 2 2 5", 1);
+    assert_eq!(validate(&font), []);
+
+    // every glyph is two lines, the space starts on line 3
+    let mut lines: Vec<String> = font.lines().map(str::to_string).collect();
+    lines[0] = "flf2a$ 2 2 5 0 0 0 0 1".to_string();
+    lines[4] = "╔═╗══@".to_string();
+    lines[7] = "╚═╝#".to_string();
+    lines[8] = "╔═╗@@".to_string();
+    lines[9] = "╚═╝@".to_string();
+    lines[11] = String::new();
+    for _ in 0..7 {
+        lines.push("╔═╗@".to_string());
+        lines.push("╚═╝@@".to_string());
+    }
+    for tag in ["0x41 A", "65 A again"] {
+        lines.push(tag.to_string());
+        lines.push("╔═╗@".to_string());
+        lines.push("╚═╝@@".to_string());
+    }
+
+    let diagnostics = validate(&lines.join("\n"));
+    let found: Vec<(usize, &Problem)> = diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.line, &diagnostic.problem))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (
+                2,
+                &Problem::CommentLinesMismatch {
+                    declared: 0,
+                    found: 1
+                }
+            ),
+            (
+                5,
+                &Problem::RowTooLong {
+                    code: 33,
+                    length: 6,
+                    max_length: 5
+                }
+            ),
+            (
+                6,
+                &Problem::InconsistentWidth {
+                    code: 33,
+                    expected: 5,
+                    found: 3
+                }
+            ),
+            (
+                8,
+                &Problem::InconsistentEndmark {
+                    code: 34,
+                    expected: '@',
+                    found: '#'
+                }
+            ),
+            (
+                9,
+                &Problem::WrongHeight {
+                    code: 35,
+                    expected: 2,
+                    found: 1
+                }
+            ),
+            (12, &Problem::MissingEndmark { code: 36 }),
+            (
+                210,
+                &Problem::DuplicateCodeTag {
+                    code: 65,
+                    first_line: 207
+                }
+            ),
+            (
+                1,
+                &Problem::CodetagCountMismatch {
+                    declared: 1,
+                    found: 2
+                }
+            ),
+        ]
+    );
+    assert_eq!(diagnostics[0].severity(), Severity::Error);
+    assert_eq!(diagnostics[1].severity(), Severity::Warning);
+
+    // negative codes and 0 can be duplicated too
+    let mut tagged = box_font("box").replacen("flf2a$ 2 2 4", "flf2a$ 2 2 5", 1);
+    tagged.push_str(&"╔═╗@\n╚═╝@@\n".repeat(7));
+    for tag in ["-2 A", "-2 B", "0 C", "0 D", "-3 E"] {
+        tagged.push_str(&format!("{tag}\n╔═╗@\n╚═╝@@\n"));
+    }
+    let duplicates: Vec<Problem> = validate(&tagged)
+        .into_iter()
+        .map(|diagnostic| diagnostic.problem)
+        .collect();
+    assert_eq!(
+        duplicates,
+        [
+            Problem::DuplicateCodeTag {
+                code: -2,
+                first_line: 207,
+            },
+            Problem::DuplicateCodeTag {
+                code: 0,
+                first_line: 213,
+            },
+        ]
+    );
+
+    // a truncated font is reported once, even when the comments run past
+    // the end of the file
+    let truncated = font.lines().take(100).collect::<Vec<_>>().join("\n");
+    for font in ["flf2a$ 2 1 5 -1 50\nshort\n", &truncated] {
+        let diagnostics = validate(font);
+        assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+        assert!(matches!(
+            diagnostics[0].problem,
+            Problem::WrongHeight { .. }
+        ));
+    }
+}
+
+#[test]
+pub fn test_font_formats() {
+    init();
+
+    let font = load_small();
+    let settings = small_settings();
+    let text = "Hello, Wörld! ¡";
+    let expected = font.convert(text, &settings).expect("failed to convert");
+
+    let flf = font.to_flf();
+    assert!(validate(&flf).is_empty());
+    let reloaded = flf.parse::<FIGfont>().expect("failed to parse flf");
+    assert_eq!(reloaded.comments, font.comments);
+    assert_eq!(reloaded.to_flf(), flf);
+    assert_eq!(reloaded.convert(text, &settings).unwrap(), expected);
+
+    let tlf = font.to_tlf();
+    assert!(tlf.starts_with("tlf2a$ "));
+    let reloaded = tlf.parse::<FIGfont>().expect("failed to parse tlf");
+    assert_eq!(reloaded.header.signature, "tlf2a");
+    assert_eq!(reloaded.convert(text, &settings).unwrap(), expected);
+
+    let compiled = font.to_compiled();
+    let reloaded = FIGfont::from_compiled(&compiled).expect("failed to load compiled font");
+    assert_eq!(reloaded.header.hardblank, '$');
+    assert_eq!(reloaded.convert(text, &settings).unwrap(), expected);
+    assert!(matches!(
+        FIGfont::from_compiled(flf.as_bytes()),
+        Err(FontLoadError::NotCompiled)
+    ));
+
+    // rows ending in '@' get a different endmark
+    let mut font = box_font("at")
+        .parse::<FIGfont>()
+        .expect("failed to parse font");
+    let at = FIGcharacter::new(vec![vec!['@', '@', '@'], vec!['@', ' ', '@']]);
+    font.characters.insert(196, at);
+    let reloaded = font.to_flf().parse::<FIGfont>().unwrap();
+    assert_eq!(
+        reloaded.characters[&196].char_lines,
+        [vec!['@', '@', '@'], vec!['@', ' ', '@']]
+    );
+}
+
+static TINY: EmbeddedFont = EmbeddedFont {
+    hardblank: '$',
+    height: 2,
+    baseline: 2,
+    max_length: 4,
+    old_layout: -1,
+    direction: None,
+    full_layout: None,
+    codetag_count: None,
+    comments: "tiny",
+    characters: &[(32, &["$", "$"]), (72, &["|-|", "| |"]), (105, &["o", "|"])],
+};
+
+#[test]
+pub fn test_embedded_font() {
+    init();
+
+    assert_eq!(TINY.glyph('H' as u32), Some(&["|-|", "| |"][..]));
+    assert_eq!(TINY.glyph('x' as u32), None);
+
+    let font = FIGfont::from(&TINY);
+    assert_eq!(font.header.comment_lines, 1);
+    let converted = font
+        .convert("Hi H", &small_settings())
+        .expect("failed to convert");
+    assert_eq!(converted, "|-|o |-|\n| || | |\n");
+
+    let source = font.to_rust("TINY");
+    assert!(source.contains(
+        "pub static TINY: ::figurust::figlet::EmbeddedFont = ::figurust::figlet::EmbeddedFont {"
+    ));
+    assert!(source.contains("    old_layout: -1,\n    direction: None,\n"));
+    assert!(source.contains("        (32, &[\"$\", \"$\"]),\n        (72, &[\"|-|\", \"| |\"]),\n"));
+
+    // rows are escaped like any other string literal
+    let small = load_small();
+    let source = small.to_rust("SMALL");
+    assert!(source.contains(r#"(92, &[" __   ", " \\ \\  ", "#));
+}
+
+#[test]
+pub fn test_tagged_characters() {
+    init();
+
+    let font = load_small();
+
+    // 95 ascii and the 96 code tagged Latin-1 characters, which redefine
+    // the 7 deutsch characters
+    assert_eq!(font.characters.len(), 95 + 96);
+    assert!(font.characters.contains_key(&('Ä' as u32)));
+    assert!(font.characters.contains_key(&('©' as u32)));
+
+    // like figlet, a bad tag ends the font without losing what came before
+    let mut source = include_str!("../fonts/small.flf").to_string();
+    source.push_str("not-a-code  BAD TAG\n");
+    source.push_str(&"@\n".repeat(4));
+    source.push_str("@@\n");
+    let font = source.parse::<FIGfont>().expect("failed to parse font");
+    assert_eq!(font.characters.len(), 95 + 96);
+}
+
+#[test]
+pub fn test_missing_character_policy() {
+    init();
+
+    let mut font = load_small();
+    let mut settings = small_settings();
+    let text = "a\u{1F600}b";
+
+    assert!(matches!(
+        font.convert(text, &settings),
+        Err(FontConvertError::MissingCharacter('\u{1F600}'))
+    ));
+
+    settings.missing_character = MissingCharacterPolicy::Skip;
+    let figure = font.render(text, &settings).expect("failed to render text");
+    assert_eq!(figure.to_string(), font.convert("ab", &settings).unwrap());
+    assert_eq!(
+        figure.substitutions(),
+        &[Substitution {
+            offset: 1,
+            original: '\u{1F600}',
+            replacement: None,
+        }]
+    );
+
+    settings.missing_character = MissingCharacterPolicy::Replace('?');
+    let figure = font.render(text, &settings).expect("failed to render text");
+    assert_eq!(figure.to_string(), font.convert("a?b", &settings).unwrap());
+    assert_eq!(figure.substitutions()[0].replacement, Some('?'));
+    assert_eq!(font.measure(text, &settings).unwrap().width, figure.width());
+
+    // small has no code 0 character, so figlet would drop it
+    settings.missing_character = MissingCharacterPolicy::CodeZero;
+    let figure = font.render(text, &settings).expect("failed to render text");
+    assert_eq!(figure.substitutions()[0].replacement, None);
+
+    let question = font.characters[&('?' as u32)].clone();
+    font.characters.insert(0, question);
+    let figure = font.render(text, &settings).expect("failed to render text");
+    assert_eq!(figure.to_string(), font.convert("a?b", &settings).unwrap());
+    assert_eq!(figure.substitutions()[0].replacement, Some('\0'));
+}
+
+#[test]
+pub fn test_control_characters() {
+    init();
+
+    let font = load_small();
+    let settings = small_settings();
+    let convert = |text| {
+        font.convert(text, &settings)
+            .expect("failed to convert text")
+    };
+
+    assert_eq!(convert("a\tb"), convert("a b"));
+    assert_eq!(convert("a\x07b\x7f"), convert("ab"));
+    for text in ["a\r\nb", "a\rb", "a\x0bb", "a\x0cb"] {
+        assert_eq!(convert(text), convert("a\nb"), "{text:?}");
+    }
+
+    // sources still point into the original input
+    let figure = font.render("a\x07\tb", &settings).unwrap();
+    assert!(figure.cells_from(3).count() > 0);
+    assert_eq!(figure.cells_from(1).count(), 0);
+
+    let figure = font.render("a\r\nb", &settings).unwrap();
+    assert!(figure.cells_from(3).all(|(_, y)| y >= 5));
+}
+
+// a tiny font where every character is drawn with box-drawing characters
+fn box_font(comment: &str) -> String {
+    let mut font = format!("flf2a$ 2 2 4 0 1\n{comment}\n$$@\n$$@@\n");
+    for _ in 33..=126 {
+        font.push_str("╔═╗@\n╚═╝@@\n");
+    }
+    font
+}
+
+#[test]
+pub fn test_unicode_glyphs() {
+    init();
+
+    let font = box_font("box drawing")
+        .parse::<FIGfont>()
+        .expect("failed to parse font");
+    let character = &font.characters[&('a' as u32)];
+    assert_eq!(character.width, 3);
+    assert_eq!(character.char_lines[0], vec!['╔', '═', '╗']);
+
+    let mut settings = small_settings();
+    for spacing in [Spacing::Smushing, Spacing::Kerning, Spacing::FullWidth] {
+        settings.spacing = spacing;
+        let converted = font.convert("a b", &settings).expect("failed to convert");
+        assert_eq!(converted, "╔═╗  ╔═╗\n╚═╝  ╚═╝\n");
+    }
+
+    // universal smushing overlaps the hardblanks of the space
+    settings.spacing = Spacing::ForceSmushing;
+    let converted = font.convert("a b", &settings).expect("failed to convert");
+    assert_eq!(converted, "╔═╗╔═╗\n╚═╝╚═╝\n");
+
+    settings.overlap = true;
+    let converted = font.convert("ab", &settings).expect("failed to convert");
+    assert_eq!(converted, "╔═╔═╗\n╚═╚═╝\n");
+
+    // like figlet, the later glyph still wins right to left, and it's the
+    // one on the left
+    settings.direction = Direction::RightToLeft;
+    let converted = font.convert("ab", &settings).expect("failed to convert");
+    assert_eq!(converted, "╔═╗═╗\n╚═╝═╝\n");
+    settings.direction = Direction::LeftToRight;
+
+    // fonts that aren't utf-8 are read as Latin-1
+    let dir = temp_dir("latin1");
+    let path = dir.join("latin1.flf");
+    let mut latin1 = box_font("caf\u{e9}").into_bytes();
+    let idx = latin1.iter().position(|&b| b == 0xc3).unwrap();
+    latin1.splice(idx..idx + 2, [0xe9]);
+    std::fs::write(&path, latin1).expect("failed to write font");
+    let font = FIGfont::from_file(&path).expect("failed to load latin-1 font");
+    assert_eq!(font.comments, "caf\u{e9}");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+pub fn test_pair_cache() {
+    init();
+
+    let font = box_font("pairs")
+        .parse::<FIGfont>()
+        .expect("failed to parse font");
+    let mut settings = small_settings();
+    settings.overlap = true;
+
+    let lay_out = |text: &str, cache: Option<&mut PairCache>| {
+        let mut fresh = PairCache::default();
+        let cache = cache.unwrap_or(&mut fresh);
+        let mut line_layout = LineLayout::new(Extent::of(&font), true, false);
+        for (source, c) in text.char_indices() {
+            let glyph = Glyph {
+                font: &font,
+                character: &font.characters[&(c as u32)],
+            };
+            line_layout.push(glyph, source, &settings, cache);
+        }
+        line_layout.into_rows(0)
+    };
+
+    // every row of the box glyphs is drawn, so each pair is remembered
+    let mut cache = PairCache::default();
+    let first = lay_out("abab", Some(&mut cache));
+    assert_eq!(cache.len(), 2);
+
+    // a second line only uses the remembered amounts, which have to match
+    // the ones worked out from scratch
+    let second = lay_out("abab", Some(&mut cache));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(first, second);
+    assert_eq!(second, lay_out("abab", None));
+}
@@ -0,0 +1,140 @@
+//! Renders FIGlet banners at compile time.
+//!
+//! Use these through `figurust` with its `macros` feature. figurust depends
+//! on this crate to re-export them, so they render with figurust-core.
+
+use std::{path::PathBuf, sync::Arc};
+
+use figurust_core::{
+    figlet::{FIGfont, SearchPath},
+    settings::Settings,
+};
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Ident, LitInt, LitStr, Token,
+};
+
+/// Renders text with a FIGlet font, expanding to a `&'static str`
+///
+/// ```ignore
+/// const BANNER: &str = figurust::banner!("MyService", font = "small", width = 80);
+/// ```
+///
+/// `font` is a font name or a path to a font file, and `font_dir` adds a
+/// directory to look for fonts in, which can be given more than once.
+/// Relative paths start from the calling crate's manifest, and that
+/// crate's `fonts` directory is searched before the standard ones.
+///
+/// Fonts that can't be found and characters the font doesn't have are
+/// compile errors. The font file is included in the build, so editing it
+/// renders the banner again.
+#[proc_macro]
+pub fn banner(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as BannerInput);
+    match input.render() {
+        Ok((banner, font_file)) => {
+            let banner = LitStr::new(&banner, input.text.span());
+            // bundled fonts don't have a file to depend on
+            let font_file = font_file.map(|path| path.to_string_lossy().into_owned());
+            let dependency = font_file.map(|path| {
+                quote!(
+                    const _: &[u8] = include_bytes!(#path);
+                )
+            });
+            quote!({
+                #dependency
+                #banner
+            })
+            .into()
+        }
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+struct BannerInput {
+    text: LitStr,
+    font: Option<LitStr>,
+    font_dirs: Vec<LitStr>,
+    width: Option<usize>,
+}
+
+impl Parse for BannerInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut banner = BannerInput {
+            text: input.parse()?,
+            font: None,
+            font_dirs: vec![],
+            width: None,
+        };
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            // allow a trailing comma
+            if input.is_empty() {
+                break;
+            }
+
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_str() {
+                "font" => banner.font = Some(input.parse()?),
+                "font_dir" => banner.font_dirs.push(input.parse()?),
+                "width" => banner.width = Some(input.parse::<LitInt>()?.base10_parse()?),
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "expected `font`, `font_dir` or `width`",
+                    ))
+                }
+            }
+        }
+
+        Ok(banner)
+    }
+}
+
+impl BannerInput {
+    /// The banner along with the file its font was loaded from
+    fn render(&self) -> syn::Result<(String, Option<PathBuf>)> {
+        let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+
+        let mut settings = Settings::default();
+        settings
+            .font_path
+            .prepend(manifest_dir.join(SearchPath::project_directory()));
+        for directory in &self.font_dirs {
+            settings
+                .font_path
+                .prepend(manifest_dir.join(directory.value()));
+        }
+        settings.width = self.width;
+        if let Some(font) = &self.font {
+            settings.font = font.value();
+        }
+
+        // errors about the font point at the font, if it was given
+        let font_span = self.font.as_ref().unwrap_or(&self.text).span();
+        let font_file = manifest_dir.join(&settings.font);
+        let (font, font_file) = if font_file.is_file() {
+            let font = FIGfont::from_file(&font_file)
+                .map(Arc::new)
+                .map_err(|e| syn::Error::new(font_span, format!("failed to load font: {e}")))?;
+            (font, Some(font_file))
+        } else {
+            let registry = settings.font_path.registry();
+            let font = registry
+                .get(&settings.font)
+                .map_err(|e| syn::Error::new(font_span, e.to_string()))?;
+            (font, registry.path(&settings.font))
+        };
+        // `include_bytes!` would take a relative path from the calling file
+        let font_file = font_file.and_then(|path| path.canonicalize().ok());
+
+        let banner = font
+            .convert(self.text.value(), &settings)
+            .map_err(|e| syn::Error::new(self.text.span(), e.to_string()))?;
+        Ok((banner, font_file))
+    }
+}
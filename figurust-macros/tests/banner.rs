@@ -0,0 +1,20 @@
+use figurust::{banner, figlet::BUNDLED_FONTS, settings::Settings};
+
+const BANNER: &str = banner!("Hi!", font = "small", width = 80);
+
+#[test]
+fn test_banner() {
+    let (_, source) = BUNDLED_FONTS[0];
+    let font = source.parse::<figurust::figlet::FIGfont>().unwrap();
+    let expected = font.convert("Hi!", &Settings::default()).unwrap();
+    assert_eq!(BANNER, expected);
+
+    // paths are relative to the manifest, and wrapping follows the width
+    let narrow = banner!(
+        "Hi Hi",
+        font = "../figurust-core/fonts/small.flf",
+        width = 12,
+    );
+    let hi = font.convert("Hi", &Settings::default()).unwrap();
+    assert_eq!(narrow, hi.repeat(2));
+}
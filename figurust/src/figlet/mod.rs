@@ -1,7 +0,0 @@
-mod chars;
-mod font;
-mod header;
-
-pub use chars::{CharacterParseError, FIGcharacter};
-pub use font::{FIGfont, FontLoadError};
-pub use header::{HeaderParseError, RawHeader};
@@ -1,10 +1,7 @@
-pub mod figlet;
-pub mod settings;
+//! FIGlet fonts and rendering, from figurust-core. The `macros` feature adds
+//! the compile-time `banner!` macro.
 
-#[cfg(test)]
-mod tests;
+pub use figurust_core::*;
 
-#[macro_use]
-extern crate serde;
-
-pub const FIGLET_FONT_SIGNATURE: &str = "flf2";
+#[cfg(feature = "macros")]
+pub use figurust_macros::banner;